@@ -0,0 +1,4 @@
+mod order_ref;
+pub use order_ref::{OrderRefAllocator, OrderRefError, SessionOrderRefRule};
+mod session;
+pub use session::{Credentials, LoginApi, Session, SessionState};
@@ -0,0 +1,180 @@
+//! OrderRef allocation for plain `YDApi`, like `setSessionOrderRefRule` and `getNextOrderRef` of `YDExtendedApi`
+
+use std::{
+    fmt,
+    sync::atomic::{AtomicBool, AtomicI32, Ordering},
+};
+
+use yd_client_sys::{
+    bindings::YDOrder, data_types::OrderGroupRefControl, spi_wrapper::YDListenerOutput, yd_error::YdError, OrderGroup,
+};
+
+/// groups 1-63 use strict OrderRef management, 0 is the normal one
+const ORDER_GROUP_COUNT: usize = 64;
+/// "User should ensure that sessionBitCount<=16" in ydApi.h
+const MAX_SESSION_BIT_COUNT: u32 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderRefError {
+    /// `session_id` has bits other than the lower `session_bit_count` bits
+    InvalidSessionRule { session_bit_count: u32, session_id: u32 },
+    /// max OrderRef of the group is not received from `notifyLogin` or `notifyGroupMaxOrderRef` yet
+    NotSeeded { order_group_id: u8 },
+    InvalidOrderGroupId { order_group_id: u8 },
+    /// no OrderRef with the session bits is left below `i32::MAX`
+    Exhausted { order_group_id: u8 },
+}
+
+impl fmt::Display for OrderRefError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSessionRule {
+                session_bit_count,
+                session_id,
+            } => write!(
+                f,
+                "session id {session_id} doesn't fit in {session_bit_count} bits, or the bit count exceeds {MAX_SESSION_BIT_COUNT}"
+            ),
+            Self::NotSeeded { order_group_id } => {
+                write!(f, "max OrderRef of group {order_group_id} is not received, wait for login")
+            }
+            Self::InvalidOrderGroupId { order_group_id } => {
+                write!(f, "order group id {order_group_id} is out of 1-{}", ORDER_GROUP_COUNT - 1)
+            }
+            Self::Exhausted { order_group_id } => write!(f, "OrderRef of group {order_group_id} is exhausted"),
+        }
+    }
+}
+
+impl std::error::Error for OrderRefError {}
+
+/// the lower `session_bit_count` bits of every allocated OrderRef are `session_id`,
+/// so sessions of the same account never collide. The default rule has no session bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SessionOrderRefRule {
+    pub session_bit_count: u32,
+    pub session_id: u32,
+}
+
+impl SessionOrderRefRule {
+    fn check(&self) -> Result<(), OrderRefError> {
+        if self.session_bit_count > MAX_SESSION_BIT_COUNT || self.session_id >> self.session_bit_count != 0 {
+            return Err(OrderRefError::InvalidSessionRule {
+                session_bit_count: self.session_bit_count,
+                session_id: self.session_id,
+            });
+        }
+        Ok(())
+    }
+
+    /// the smallest OrderRef greater than `max_order_ref` with the session bits
+    fn next_after(&self, max_order_ref: i32) -> Option<i32> {
+        let step = 1i64 << self.session_bit_count;
+        let max_order_ref = i64::from(max_order_ref.max(0));
+        let candidate = (max_order_ref & !(step - 1)) | i64::from(self.session_id);
+        let next = if candidate > max_order_ref { candidate } else { candidate + step };
+        i32::try_from(next).ok()
+    }
+}
+
+/// thread-safe OrderRef allocator, seeded by `notifyLogin` and `notifyGroupMaxOrderRef`.
+///
+/// It keeps the max OrderRef of each group, and allocates above it:
+///
+/// - group 0 and `YD_GORF_Increase` groups: the next OrderRef with the session bits
+/// - `YD_GORF_IncreaseOne` groups: exactly max + 1, session bits are ignored because ydServer accepts no other value
+///
+/// After a reconnect, the new `notifyLogin` only raises the max of group 0, while `notifyGroupMaxOrderRef` replaces the
+/// max of groups 1-63, because ydServer rejects any gap in `YD_GORF_IncreaseOne` groups
+pub struct OrderRefAllocator {
+    rule: SessionOrderRefRule,
+    max_order_refs: [AtomicI32; ORDER_GROUP_COUNT],
+    login_seeded: AtomicBool,
+    groups_seeded: AtomicBool,
+}
+
+impl OrderRefAllocator {
+    pub fn new(rule: SessionOrderRefRule) -> Result<Self, OrderRefError> {
+        rule.check()?;
+        Ok(Self {
+            rule,
+            max_order_refs: std::array::from_fn(|_| AtomicI32::new(0)),
+            login_seeded: AtomicBool::new(false),
+            groups_seeded: AtomicBool::new(false),
+        })
+    }
+
+    pub fn rule(&self) -> SessionOrderRefRule {
+        self.rule
+    }
+
+    /// `maxOrderRef` of a successful `notifyLogin`
+    pub fn on_login(&self, max_order_ref: i32) {
+        self.max_order_refs[0].fetch_max(max_order_ref, Ordering::AcqRel);
+        self.login_seeded.store(true, Ordering::Release);
+    }
+
+    /// `groupMaxOrderRef` of `notifyGroupMaxOrderRef`, indexed by OrderGroupID, the element of group 0 is not used
+    pub fn on_group_max_order_ref(&self, group_max_order_ref: &[i32]) {
+        for (slot, max_order_ref) in self.max_order_refs.iter().zip(group_max_order_ref).skip(1) {
+            slot.store(*max_order_ref, Ordering::Release);
+        }
+        self.groups_seeded.store(true, Ordering::Release);
+    }
+
+    /// follows orders of other sessions, and `MaxOrderRef` reported with `YD_ERROR_InvalidGroupOrderRef`
+    pub fn on_order(&self, order: &YDOrder) {
+        let Some(slot) = self.max_order_refs.get(order.OrderGroupID as usize) else {
+            return;
+        };
+        if order.ErrorNo == 0 {
+            slot.fetch_max(order.OrderRef, Ordering::AcqRel);
+        } else if order.OrderGroupID != 0 && order.ErrorNo == YdError::InvalidGroupOrderRef.code() {
+            // MaxOrderRef 和 OrderSysID 在同一个 union 中
+            slot.store(unsafe { order.__bindgen_anon_2.MaxOrderRef }, Ordering::Release);
+        }
+    }
+
+    /// feeds `NotifyLogin`, `NotifyGroupMaxOrderRef` and `NotifyOrder` of the listener stream, ignores other packets
+    pub fn handle(&self, output: &YDListenerOutput) {
+        match output {
+            YDListenerOutput::NotifyLogin(packet) if packet.error_no == 0 => self.on_login(packet.max_order_ref),
+            YDListenerOutput::NotifyGroupMaxOrderRef(packet) => self.on_group_max_order_ref(&packet.group_max_order_ref),
+            YDListenerOutput::NotifyOrder(packet) => self.on_order(&packet.order),
+            _ => {}
+        }
+    }
+
+    /// OrderRef of group 0
+    pub fn next_order_ref(&self) -> Result<i32, OrderRefError> {
+        if !self.login_seeded.load(Ordering::Acquire) {
+            return Err(OrderRefError::NotSeeded { order_group_id: 0 });
+        }
+        let rule = self.rule;
+        allocate(&self.max_order_refs[0], 0, |max_order_ref| rule.next_after(max_order_ref))
+    }
+
+    /// OrderRef of a strict management group, set `GroupOrderRefControl` of the order to `group.ref_control`
+    pub fn next_group_order_ref(&self, group: OrderGroup) -> Result<i32, OrderRefError> {
+        let order_group_id = group.id;
+        let slot = match self.max_order_refs.get(order_group_id as usize) {
+            Some(slot) if order_group_id != 0 => slot,
+            _ => return Err(OrderRefError::InvalidOrderGroupId { order_group_id }),
+        };
+        if !self.groups_seeded.load(Ordering::Acquire) {
+            return Err(OrderRefError::NotSeeded { order_group_id });
+        }
+        let rule = self.rule;
+        match group.ref_control {
+            OrderGroupRefControl::Increase => allocate(slot, order_group_id, |max_order_ref| rule.next_after(max_order_ref)),
+            OrderGroupRefControl::IncreaseOne => allocate(slot, order_group_id, |max_order_ref| max_order_ref.checked_add(1)),
+        }
+    }
+}
+
+fn allocate(slot: &AtomicI32, order_group_id: u8, next: impl Fn(i32) -> Option<i32>) -> Result<i32, OrderRefError> {
+    slot.fetch_update(Ordering::AcqRel, Ordering::Acquire, &next)
+        .ok()
+        .and_then(next)
+        .ok_or(OrderRefError::Exhausted { order_group_id })
+}
@@ -0,0 +1,196 @@
+//! login and reconnection of an api, driven by `notifyEvent`, `notifyReadyForLogin`, `notifyLogin`,
+//! `notifyFinishInit` and `notifyCaughtUp`
+
+use std::{ffi::NulError, fmt};
+
+use tokio::sync::watch;
+use yd_client_sys::{
+    bindings::{YDApi, YDExtendedApi},
+    data_types::ApiEvent,
+    spi_wrapper::YDListenerOutput,
+    ApiRecord, YDApiHandle,
+};
+
+/// a session goes through the states in order, a reconnect goes back to `Disconnected`.
+/// `notifyFinishInit` is only sent once per api, so after a reconnect `LoggedIn` is followed by `CaughtUp` directly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionState {
+    Disconnected,
+    /// `notifyReadyForLogin` is received, login is not sent yet
+    ReadyForLogin,
+    LoggingIn,
+    LoggedIn,
+    /// `notifyFinishInit`, all api functions can be called, but orders and trades are not all received
+    Initialized,
+    /// `notifyCaughtUp`, all information up to the recent login is received
+    CaughtUp,
+    /// `YD_AE_ServerRestarted`, all data received may be invalid, the api is being destroyed and a new one must be created.
+    /// It is terminal, the session ignores all notifications afterwards. Only reached by a handle passed to
+    /// `Session::attach`, otherwise libyd exits the process once `notifyEvent` returns, see ydApi.h
+    ServerRestarted,
+}
+
+/// arguments of `login`
+#[derive(Clone)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+    pub app_id: String,
+    pub auth_code: String,
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("app_id", &self.app_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// apis that can send `login`, the session calls it on `notifyReadyForLogin`
+pub trait LoginApi {
+    /// whether the request is sent
+    fn login(&mut self, credentials: &Credentials) -> bool;
+}
+
+impl LoginApi for YDApi {
+    fn login(&mut self, credentials: &Credentials) -> bool {
+        // credentials are checked by `Session::new`, they never contain NUL
+        let Credentials {
+            username,
+            password,
+            app_id,
+            auth_code,
+        } = credentials;
+        matches!(YDApi::login(self, username, password, app_id, auth_code), Ok(true))
+    }
+}
+
+impl LoginApi for YDExtendedApi {
+    fn login(&mut self, credentials: &Credentials) -> bool {
+        LoginApi::login(&mut self._base, credentials)
+    }
+}
+
+/// login is not sent once `startDestroy` is called, e.g. by the hook of `Session::attach`
+impl<A: ApiRecord> LoginApi for YDApiHandle<A> {
+    fn login(&mut self, credentials: &Credentials) -> bool {
+        self.api_mut().is_ok_and(|mut api| LoginApi::login(api.as_yd_api(), credentials))
+    }
+}
+
+/// state machine of a session, feed it with the listener stream by `handle`, and watch the state by `subscribe`:
+///
+/// ```ignore
+/// let mut session = Session::new(credentials)?;
+/// let mut state = session.subscribe();
+/// session.attach(&mut handle);
+/// handle.start(Box::new(spi))?;
+/// while let Some(output) = spi_stream.next().await {
+///     session.handle(&output, &mut handle);
+/// }
+/// ```
+pub struct Session {
+    credentials: Credentials,
+    state_sender: watch::Sender<SessionState>,
+    /// re-login when `notifyReadyForLogin` reports a failed login, which is off by default,
+    /// because wrong credentials would be sent again and again
+    pub relogin_after_failure: bool,
+    last_login_error: Option<i32>,
+}
+
+impl Session {
+    /// credentials containing NUL are rejected here, instead of failing every login
+    pub fn new(credentials: Credentials) -> Result<Self, NulError> {
+        for field in [
+            &credentials.username,
+            &credentials.password,
+            &credentials.app_id,
+            &credentials.auth_code,
+        ] {
+            std::ffi::CString::new(field.as_str())?;
+        }
+        Ok(Self {
+            credentials,
+            state_sender: watch::channel(SessionState::Disconnected).0,
+            relogin_after_failure: false,
+            last_login_error: None,
+        })
+    }
+
+    pub fn state(&self) -> SessionState {
+        *self.state_sender.borrow()
+    }
+
+    /// receives every state change, e.g. `receiver.wait_for(|state| *state == SessionState::CaughtUp).await`
+    pub fn subscribe(&self) -> watch::Receiver<SessionState> {
+        self.state_sender.subscribe()
+    }
+
+    /// survive `YD_AE_ServerRestarted`: the handle calls `startDestroy` inside `notifyEvent`, so libyd doesn't
+    /// exit the process, and the session reaches `ServerRestarted` when the event comes out of the stream.
+    /// Call it before `start`, then shut the handle down and create a new api after `ServerRestarted`
+    pub fn attach<A: ApiRecord>(&self, handle: &mut YDApiHandle<A>) {
+        handle.set_destroy_on_server_restart(true);
+    }
+
+    /// `errorNo` of the last failed `notifyLogin`, cleared by a successful one
+    pub fn last_login_error(&self) -> Option<i32> {
+        self.last_login_error
+    }
+
+    /// send `login` with the stored credentials now, e.g. after a failed login when `relogin_after_failure` is off
+    pub fn login<A: LoginApi>(&mut self, api: &mut A) -> bool {
+        if self.state() == SessionState::ServerRestarted {
+            return false;
+        }
+        let sent = api.login(&self.credentials);
+        if sent {
+            self.set_state(SessionState::LoggingIn);
+        }
+        sent
+    }
+
+    /// update the state by a listener packet, and login on `notifyReadyForLogin`. Other packets are ignored
+    pub fn handle<A: LoginApi>(&mut self, output: &YDListenerOutput, api: &mut A) {
+        if self.state() == SessionState::ServerRestarted {
+            return;
+        }
+        match output {
+            YDListenerOutput::NotifyEvent(packet) => match ApiEvent::try_from(packet.api_event) {
+                Ok(ApiEvent::ServerRestarted) => self.set_state(SessionState::ServerRestarted),
+                // 切换服务器后需要重新登录
+                Ok(ApiEvent::TCPTradeDisconnected | ApiEvent::ServerSwitched) => self.set_state(SessionState::Disconnected),
+                _ => {}
+            },
+            YDListenerOutput::NotifyReadyForLogin(packet) => {
+                self.set_state(SessionState::ReadyForLogin);
+                if !packet.has_login_failed || self.relogin_after_failure {
+                    self.login(api);
+                }
+            }
+            YDListenerOutput::NotifyLogin(packet) => {
+                if packet.error_no == 0 {
+                    self.last_login_error = None;
+                    self.set_state(SessionState::LoggedIn);
+                } else {
+                    // libyd 会再次调用 notifyReadyForLogin(true)
+                    self.last_login_error = Some(packet.error_no);
+                    self.set_state(SessionState::ReadyForLogin);
+                }
+            }
+            YDListenerOutput::NotifyFinishInit(_) => self.set_state(SessionState::Initialized),
+            YDListenerOutput::NotifyCaughtUp(_) => self.set_state(SessionState::CaughtUp),
+            _ => {}
+        }
+    }
+
+    fn set_state(&self, state: SessionState) {
+        self.state_sender.send_if_modified(|current| {
+            let modified = *current != state;
+            *current = state;
+            modified
+        });
+    }
+}
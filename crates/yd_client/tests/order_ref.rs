@@ -0,0 +1,81 @@
+use std::{sync::Arc, thread};
+
+use yd_client::{OrderRefAllocator, OrderRefError, SessionOrderRefRule};
+use yd_client_sys::{bindings::YDOrder, data_types::OrderGroupRefControl, yd_error::YdError, OrderGroup};
+
+#[test]
+fn test_session_bits() {
+    let rule = SessionOrderRefRule {
+        session_bit_count: 4,
+        session_id: 3,
+    };
+    let allocator = OrderRefAllocator::new(rule).unwrap();
+    assert_eq!(allocator.next_order_ref(), Err(OrderRefError::NotSeeded { order_group_id: 0 }));
+    allocator.on_login(100);
+    assert_eq!(allocator.next_order_ref(), Ok(115));
+    assert_eq!(allocator.next_order_ref(), Ok(131));
+    // a reconnect never moves OrderRef backwards
+    allocator.on_login(40);
+    assert_eq!(allocator.next_order_ref(), Ok(147));
+    allocator.on_login(1000);
+    assert_eq!(allocator.next_order_ref(), Ok(1011));
+
+    let invalid_rule = SessionOrderRefRule {
+        session_bit_count: 2,
+        session_id: 4,
+    };
+    assert!(OrderRefAllocator::new(invalid_rule).is_err());
+}
+
+#[test]
+fn test_order_groups() {
+    let allocator = OrderRefAllocator::new(SessionOrderRefRule::default()).unwrap();
+    let increase_one = OrderGroup {
+        id: 2,
+        ref_control: OrderGroupRefControl::IncreaseOne,
+    };
+    assert_eq!(
+        allocator.next_group_order_ref(increase_one),
+        Err(OrderRefError::NotSeeded { order_group_id: 2 })
+    );
+    let mut group_max_order_ref = [0; 64];
+    group_max_order_ref[2] = 10;
+    allocator.on_group_max_order_ref(&group_max_order_ref);
+    assert_eq!(allocator.next_group_order_ref(increase_one), Ok(11));
+    assert_eq!(allocator.next_group_order_ref(increase_one), Ok(12));
+
+    // ydServer reports the real max when an OrderRef is rejected
+    let mut rejected = YDOrder {
+        OrderGroupID: 2,
+        OrderRef: 12,
+        ErrorNo: YdError::InvalidGroupOrderRef.code(),
+        ..Default::default()
+    };
+    rejected.__bindgen_anon_2.MaxOrderRef = 11;
+    allocator.on_order(&rejected);
+    assert_eq!(allocator.next_group_order_ref(increase_one), Ok(12));
+
+    let invalid_group = OrderGroup {
+        id: 64,
+        ref_control: OrderGroupRefControl::Increase,
+    };
+    assert_eq!(
+        allocator.next_group_order_ref(invalid_group),
+        Err(OrderRefError::InvalidOrderGroupId { order_group_id: 64 })
+    );
+}
+
+#[test]
+fn test_concurrent_allocation() {
+    let allocator = Arc::new(OrderRefAllocator::new(SessionOrderRefRule::default()).unwrap());
+    allocator.on_login(0);
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let allocator = allocator.clone();
+            thread::spawn(move || (0..1000).map(|_| allocator.next_order_ref().unwrap()).collect::<Vec<_>>())
+        })
+        .collect();
+    let mut order_refs: Vec<i32> = threads.into_iter().flat_map(|thread| thread.join().unwrap()).collect();
+    order_refs.sort();
+    assert_eq!(order_refs, (1..=4000).collect::<Vec<_>>());
+}
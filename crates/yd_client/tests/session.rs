@@ -0,0 +1,95 @@
+use yd_client::{Credentials, LoginApi, Session, SessionState};
+use yd_client_sys::{
+    data_types::ApiEvent,
+    spi_wrapper::{
+        YDListenerNotifyCaughtUpPacket, YDListenerNotifyEventPacket, YDListenerNotifyFinishInitPacket,
+        YDListenerNotifyLoginPacket, YDListenerNotifyReadyForLoginPacket, YDListenerOutput,
+    },
+};
+
+#[derive(Default)]
+struct MockApi {
+    logins: Vec<String>,
+}
+
+impl LoginApi for MockApi {
+    fn login(&mut self, credentials: &Credentials) -> bool {
+        self.logins.push(credentials.username.clone());
+        true
+    }
+}
+
+fn credentials() -> Credentials {
+    Credentials {
+        username: "trader".to_string(),
+        password: "password".to_string(),
+        app_id: "app".to_string(),
+        auth_code: "code".to_string(),
+    }
+}
+
+fn event(api_event: ApiEvent) -> YDListenerOutput {
+    YDListenerOutput::NotifyEvent(YDListenerNotifyEventPacket {
+        api_event: api_event as i32,
+    })
+}
+
+fn ready_for_login(has_login_failed: bool) -> YDListenerOutput {
+    YDListenerOutput::NotifyReadyForLogin(YDListenerNotifyReadyForLoginPacket { has_login_failed })
+}
+
+fn login(error_no: i32) -> YDListenerOutput {
+    YDListenerOutput::NotifyLogin(YDListenerNotifyLoginPacket {
+        error_no,
+        max_order_ref: 0,
+        is_monitor: false,
+    })
+}
+
+#[test]
+fn test_login_and_reconnect() {
+    let mut api = MockApi::default();
+    let mut session = Session::new(credentials()).unwrap();
+    let mut receiver = session.subscribe();
+    assert_eq!(session.state(), SessionState::Disconnected);
+
+    session.handle(&ready_for_login(false), &mut api);
+    assert_eq!((session.state(), api.logins.len()), (SessionState::LoggingIn, 1));
+    session.handle(&login(0), &mut api);
+    session.handle(&YDListenerOutput::NotifyFinishInit(YDListenerNotifyFinishInitPacket {}), &mut api);
+    assert_eq!(*receiver.borrow_and_update(), SessionState::Initialized);
+    session.handle(&YDListenerOutput::NotifyCaughtUp(YDListenerNotifyCaughtUpPacket {}), &mut api);
+    assert_eq!(session.state(), SessionState::CaughtUp);
+
+    // libyd reconnects by itself, the session logs in again without notifyFinishInit
+    session.handle(&event(ApiEvent::TCPTradeDisconnected), &mut api);
+    assert_eq!(*receiver.borrow_and_update(), SessionState::Disconnected);
+    session.handle(&ready_for_login(false), &mut api);
+    session.handle(&login(0), &mut api);
+    session.handle(&YDListenerOutput::NotifyCaughtUp(YDListenerNotifyCaughtUpPacket {}), &mut api);
+    assert_eq!((session.state(), api.logins.len()), (SessionState::CaughtUp, 2));
+}
+
+#[test]
+fn test_failed_login_and_server_restart() {
+    let mut api = MockApi::default();
+    let mut session = Session::new(credentials()).unwrap();
+    session.handle(&ready_for_login(false), &mut api);
+    session.handle(&login(3), &mut api);
+    assert_eq!((session.state(), session.last_login_error()), (SessionState::ReadyForLogin, Some(3)));
+    // failed logins are not retried unless relogin_after_failure is set
+    session.handle(&ready_for_login(true), &mut api);
+    assert_eq!((session.state(), api.logins.len()), (SessionState::ReadyForLogin, 1));
+    session.relogin_after_failure = true;
+    session.handle(&ready_for_login(true), &mut api);
+    assert_eq!((session.state(), api.logins.len()), (SessionState::LoggingIn, 2));
+
+    session.handle(&event(ApiEvent::ServerRestarted), &mut api);
+    assert_eq!(session.state(), SessionState::ServerRestarted);
+    session.handle(&ready_for_login(false), &mut api);
+    assert_eq!((session.state(), api.logins.len()), (SessionState::ServerRestarted, 2));
+
+    let mut invalid = credentials();
+    invalid.password = "pass\0word".to_string();
+    assert!(Session::new(invalid).is_err());
+}
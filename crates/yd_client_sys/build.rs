@@ -12,10 +12,15 @@ extern crate lazy_static;
 mod build_utils;
 
 use crate::build_utils::{process_children, HandlerConfigs};
-use build_utils::{create_handlers, HandlerMap};
+use build_utils::{
+    create_handlers,
+    sdk::{resolve_sdk, YdSdk},
+    HandlerMap,
+};
 
 lazy_static! {
-    static ref THIRD_PARTY_PROJECT_DIR: PathBuf = env::current_dir().unwrap().join("thirdparty");
+    static ref THIRD_PARTY_PROJECT_DIR: PathBuf = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("thirdparty");
+    static ref SDK: YdSdk = resolve_sdk(&THIRD_PARTY_PROJECT_DIR);
 }
 
 macro_rules! console_debug {
@@ -25,48 +30,80 @@ macro_rules! console_debug {
 }
 
 fn main() {
-    println!("cargo:rerun-if-changed=thirdparty/wrapper.hpp");
-    let generated_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("src/generated");
-    if !generated_dir.exists() {
-        fs::create_dir_all(&generated_dir).expect("Couldn't create generated directory");
-    }
-    create_mod_file(&generated_dir);
-
+    track_inputs();
+    link_yd();
+    // 运行时用 getYDVersion 检查实际加载的 libyd 是否是编译时选择的版本
+    println!("cargo:rustc-env=YD_CLIENT_SYS_SDK_VERSION={}", SDK.version);
+    let generated_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    generate_data_types(&generated_dir);
+    generate_string_fields(&generated_dir);
+    generate_accessors(&generated_dir);
+    generate_yd_error(&generated_dir);
     generate_type(&generated_dir);
-    clang_sys::load().expect("");
+    clang_sys::load()
+        .expect("Unable to load libclang, install it or set LIBCLANG_PATH to the directory containing it");
     let binding = Clang::new().unwrap();
     let index = Index::new(&binding, false, false);
     let wrapper_hpp_path = THIRD_PARTY_PROJECT_DIR.join("wrapper.hpp");
-    let library_header_ast = index.parser(wrapper_hpp_path).parse().unwrap();
+    let include_arg = format!("-I{}", SDK.include_dir().display());
+    let library_header_ast = index
+        .parser(wrapper_hpp_path)
+        .arguments(&["-x", "c++", "-std=c++11", &include_arg])
+        .parse()
+        .unwrap();
     let entity = library_header_ast.get_entity();
     let handlers = create_handlers();
     generate_api_wrapper(&entity, &handlers, &generated_dir);
     generate_spi_wrapper(&entity, &handlers, &generated_dir);
 }
 
-fn create_mod_file(generated_dir: &Path) {
-    let mod_file_path = generated_dir.join("mod.rs");
-    let mut mod_file = File::create(&mod_file_path).expect("Couldn't create mod.rs file");
-    writeln!(mod_file, "pub mod bindings;").expect("Couldn't write to mod.rs file");
-    writeln!(mod_file, "pub mod api_wrapper;").expect("Couldn't write to mod.rs file");
-    writeln!(mod_file, "pub mod spi_wrapper;").expect("Couldn't write to mod.rs file");
+/// rerun when any header, SDK data file or generator source changes, not only wrapper.hpp
+fn track_inputs() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=LIBCLANG_PATH");
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let mut inputs = vec![
+        THIRD_PARTY_PROJECT_DIR.join("wrapper.hpp"),
+        SDK.dir.join("error_code.csv"),
+    ];
+    collect_files(&SDK.include_dir(), &mut inputs);
+    collect_files(&manifest_dir.join("build_utils"), &mut inputs);
+    for input in inputs {
+        println!("cargo:rerun-if-changed={}", input.display());
+    }
 }
 
-/// 用 bindgen 生成与 C++ 代码兼容的 rust 的类型，生成的东西非常基本，还需要通过 unsafe 调用
-fn generate_type(generated_dir: &Path) {
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries.map(|entry| entry.expect("Unable to read directory").path()).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// libyd is linked unless it is loaded at runtime by the `dlopen` feature
+fn link_yd() {
     println!("cargo:rustc-link-lib=dylib=stdc++");
+    if env::var_os("CARGO_FEATURE_DLOPEN").is_some() {
+        // libyd 由 YdLibrary::load 在运行时加载，不链接也不写入 rpath
+        return;
+    }
 
     // Determine the platform-specific library directory
+    // win64/ 不使用：MSVC 把同名的虚函数重载按声明的逆序排在一起，生成的 v-table 按 Itanium ABI 的声明顺序排列，见 lib.rs
     let lib_dir = match env::var("CARGO_CFG_TARGET_OS").as_deref() {
         Ok("linux") => "linux64",
-        Ok("windows") => "win64",
-        _ => panic!("Unsupported OS"),
+        _ => panic!("Unsupported OS, only linux is supported"),
     };
 
-    let lib_path = THIRD_PARTY_PROJECT_DIR
-        .join("ydClient")
-        .join("ydAPI_c++")
-        .join(lib_dir);
+    let lib_path = SDK.dir.join(lib_dir);
     assert!(
         lib_path.exists(),
         "Library path does not exist: {:?}",
@@ -76,7 +113,10 @@ fn generate_type(generated_dir: &Path) {
     println!("cargo:rustc-link-search=native={}", lib_path.display());
     println!("cargo:rustc-link-arg=-Wl,-rpath,{}", lib_path.display());
     println!("cargo:rustc-link-lib=dylib=yd");
+}
 
+/// 用 bindgen 生成与 C++ 代码兼容的 rust 的类型，生成的东西非常基本，还需要通过 unsafe 调用
+fn generate_type(generated_dir: &Path) {
     let wrapper_header_path = THIRD_PARTY_PROJECT_DIR
         .join("wrapper.hpp")
         .to_str()
@@ -97,7 +137,7 @@ fn generate_type(generated_dir: &Path) {
         .generate_comments(false) //不需注释,默认true
         .derive_copy(true)
         .derive_hash(false) //不要实现hash
-        .clang_arg(format!("-I{}", lib_path.join("include").display())) // Adjust include path as necessary
+        .clang_arg(format!("-I{}", SDK.include_dir().display())) // wrapper.hpp includes headers of the selected SDK
         .generate()
         .expect("Unable to generate bindings");
     // TODO: fix "expected trait, found struct `YDListener`"
@@ -109,12 +149,62 @@ fn generate_type(generated_dir: &Path) {
     // patch_generated_binding(&file_path);
 }
 
+/// ydDataStruct.h 中定长字符串字段的 setter，检查长度和 `\0`
+fn generate_string_fields(generated_dir: &Path) {
+    let data_type_header = fs::read_to_string(SDK.include_dir().join("ydDataType.h")).expect("Unable to read ydDataType.h");
+    let data_struct_header =
+        fs::read_to_string(SDK.include_dir().join("ydDataStruct.h")).expect("Unable to read ydDataStruct.h");
+    let file_content = build_utils::string_fields::generate_string_fields(&data_type_header, &data_struct_header);
+    let file_path = generated_dir.join("string_fields.rs");
+    let mut file = File::create(&file_path).expect("Unable to create string_fields.rs");
+    file.write_all(file_content.as_bytes())
+        .expect("Failed to write to string_fields.rs");
+}
+
+/// ydDataStruct.h 中各个类的只读 accessor，bindgen 生成的字段是 char 数组、int 常量和裸指针
+fn generate_accessors(generated_dir: &Path) {
+    let data_type_header = fs::read_to_string(SDK.include_dir().join("ydDataType.h")).expect("Unable to read ydDataType.h");
+    let data_struct_header =
+        fs::read_to_string(SDK.include_dir().join("ydDataStruct.h")).expect("Unable to read ydDataStruct.h");
+    let file_content = build_utils::accessors::generate_accessors(&data_type_header, &data_struct_header);
+    let file_path = generated_dir.join("accessors.rs");
+    let mut file = File::create(&file_path).expect("Unable to create accessors.rs");
+    file.write_all(file_content.as_bytes())
+        .expect("Failed to write to accessors.rs");
+}
+
+/// 从 ydDataType.h 的常量生成 enum 和 bitflags，bindgen 只会生成零散的 i32 常量
+fn generate_data_types(generated_dir: &Path) {
+    let header_path = SDK.include_dir().join("ydDataType.h");
+    let header = fs::read_to_string(&header_path).expect("Unable to read ydDataType.h");
+    let file_content = build_utils::data_types::generate_data_types(&header);
+    let file_path = generated_dir.join("data_types.rs");
+    let mut file = File::create(&file_path).expect("Unable to create data_types.rs");
+    file.write_all(file_content.as_bytes())
+        .expect("Failed to write to data_types.rs");
+}
+
+/// 从 ydError.h 和 error_code.csv 生成 `YdError`，包括交易所返回的错误码及其中文信息
+fn generate_yd_error(generated_dir: &Path) {
+    let header = fs::read_to_string(SDK.include_dir().join("ydError.h")).expect("Unable to read ydError.h");
+    // 不是每个版本的 SDK 都带有 error_code.csv，没有时只生成错误码，不生成中文信息
+    let csv_content = fs::read_to_string(SDK.dir.join("error_code.csv")).unwrap_or_else(|_| {
+        println!("cargo:warning=error_code.csv is not found in {}, error messages are not generated", SDK.dir.display());
+        String::new()
+    });
+    let file_content = build_utils::yd_error::generate_yd_error_file(&header, &csv_content);
+    let file_path = generated_dir.join("yd_error.rs");
+    let mut file = File::create(&file_path).expect("Unable to create yd_error.rs");
+    file.write_all(file_content.as_bytes())
+        .expect("Failed to write to yd_error.rs");
+}
+
 /// 生成用于主动调用的 API 的 unsafe fn wrapper，以免每次在业务代码里调用 API 都要手动写
 fn generate_api_wrapper(entity: &Entity, handlers: &HandlerMap, generated_dir: &Path) {
     let mut configs = HandlerConfigs::default();
     configs.record_flavor = build_utils::handlers::handle_record::RecordFlavor::API;
     let mut lines: Vec<String> = Vec::new();
-    lines.push(format!("use crate::{{bindings::*, spi_wrapper::{{YDListenerTrait, YD_LISTENER_VTABLE}}}};\n\n"));
+    lines.push(format!("use crate::{{api_handle::ApiDestroyPhase, bindings::*, query_result::{{QueryResult, RawQueryResult}}, spi_wrapper::{{YDExtendedListenerFat, YDExtendedListenerTrait, YDListenerFat, YDListenerTrait}}}};\n\n"));
     lines.extend(process_children(entity, handlers, &mut configs));
     let file_content = lines.join("");
     let file_path = generated_dir.join("api_wrapper.rs");
@@ -128,7 +218,16 @@ fn generate_spi_wrapper(entity: &Entity, handlers: &HandlerMap, generated_dir: &
     let mut configs = HandlerConfigs::default();
     configs.record_flavor = build_utils::handlers::handle_record::RecordFlavor::SPI;
     let mut lines = process_children(entity, handlers, &mut configs);
-    lines.push(format!("use crate::bindings::*;\n\n"));
+    lines.push(format!(
+        r#"use crate::{{api_handle::ApiDestroyPhase, bindings::*}};
+use crate::{{
+    spi_queue::{{SpiQueue, SpiQueueConfig, SpiQueueItem, SpiQueueMetrics, SpiQueueSender, SpiSink}},
+    spsc_ring::{{spsc_ring, RingConsumer, RingProducer, SpiRingConfig}},
+}};
+use futures::stream::Stream;
+use std::{{pin::Pin, sync::Arc}};
+"#
+    ));
     let file_content = lines.join("");
     let file_path = generated_dir.join("spi_wrapper.rs");
     let mut file = File::create(&file_path).expect("Unable to create spi_wrapper.rs");
@@ -0,0 +1,228 @@
+use inflector::Inflector;
+
+use super::{
+    data_struct::{parse_data_struct_classes, DataStructClass, DataStructField},
+    data_types::{get_data_type_sections, get_exchange_dependent_sections},
+    string_fields::parse_string_typedefs,
+};
+
+/// 这些字段没有 `Refer to "X" section` 注释，或者注释写错了（`YDInputQuote::BidHedgeFlag` 写的是 "Offset Flag"）
+const FIELD_SECTIONS: [(&str, &str); 13] = [
+    ("BidHedgeFlag", "Hedge Flag"),
+    ("AskHedgeFlag", "Hedge Flag"),
+    ("HedgeFlag", "Hedge Flag"),
+    ("Direction", "Direction"),
+    ("PositionDirection", "Position Direction"),
+    ("PositionDate", "Position Date"),
+    ("CombHedgeFlag", "Combine Hedge Flag"),
+    ("ExchangeFlag", "Exchange Flag"),
+    ("ConnectionStatus", "Exchange Connection Status"),
+    ("GeneralRiskParamType", "General Risk Param Types"),
+    ("MarginModelID", "Margin Model ID"),
+    ("TradingStatus", "Trading Status"),
+    ("TradingRight", "Trade Right"),
+];
+
+/// 保留给 libyd 的字段，不生成 accessor
+const HIDDEN_FIELD_PREFIXES: [&str; 6] = ["SystemUse", "m_SystemUse", "InternalUse", "pInternalUse", "Padding", "Useless"];
+
+struct Sections {
+    /// (注释标题, 类型名, 是否为 bitflags)
+    data_types: Vec<(String, String, bool)>,
+    /// (注释标题, [(交易所类别, 类型名)])，同一个值在期货交易所和证券交易所含义不同
+    exchange_dependent: Vec<(String, Vec<(String, String)>)>,
+    string_typedefs: Vec<(String, usize)>,
+}
+
+impl Sections {
+    /// "YD QuoteFlag" 对应标题 "YD QuoteFlag, can be bitwise or of following flags"
+    fn find(&self, section: &str) -> Option<&(String, String, bool)> {
+        self.data_types
+            .iter()
+            .find(|(title, _, _)| title == section || title.split(',').next() == Some(section))
+    }
+}
+
+fn get_section(field: &DataStructField) -> Option<&str> {
+    if let Some((_, section)) = FIELD_SECTIONS.iter().find(|(name, _)| *name == field.name) {
+        return Some(section);
+    }
+    // "Refer to "Order Status" section" 或 "Bit map, refer to "Account Flag" section"
+    let comment = field.comment.as_deref()?;
+    let start = comment.find("Refer to \"").or_else(|| comment.find("refer to \""))? + "refer to \"".len();
+    // `Set by ydAPI, refer to "ydError.h"` 不是 ydDataType.h 中的常量
+    comment[start..].split_once("\" section").map(|(section, _)| section)
+}
+
+/// 定长字符串字段，`char LegDirction[2]` 这样很短的 char 数组是枚举值，不是字符串
+fn generate_string_accessor(field: &DataStructField, sections: &Sections) -> Option<String> {
+    if field.is_pointer {
+        return None;
+    }
+    let type_doc = match &field.array {
+        None => {
+            sections.string_typedefs.iter().find(|(name, _)| *name == field.type_name)?;
+            format!("`{}`", field.type_name)
+        }
+        Some(array) if field.type_name == "char" => {
+            let length: usize = array.strip_prefix('[')?.strip_suffix(']')?.parse().ok()?;
+            if length < 8 {
+                return None;
+            }
+            format!("`char{array}`")
+        }
+        Some(_) => return None,
+    };
+    let name = &field.name;
+    Some(format!(
+        r#"    /// `{name}` is {type_doc}, empty if it is not valid UTF-8
+    pub fn {snake_name}(&self) -> &str {{
+        get_fixed_str(&self.{name})
+    }}
+"#,
+        snake_name = Inflector::to_snake_case(name)
+    ))
+}
+
+fn generate_data_type_accessor(class: &DataStructClass, field: &DataStructField, sections: &Sections) -> Option<String> {
+    if field.is_pointer || field.array.is_some() {
+        return None;
+    }
+    let section = get_section(field)?;
+    let Some((_, type_name, is_bitflags)) = sections.find(section) else {
+        println!(
+            "cargo:warning=section \"{section}\" of {}::{} is not found in ydDataType.h, the accessor is not generated",
+            class.name, field.name
+        );
+        return None;
+    };
+    // 生成的 enum 和 bitflags 都基于 i32，char 和 short 字段需要转换
+    let value = match field.type_name.as_str() {
+        "int" => format!("self.{}", field.name),
+        _ => format!("self.{} as i32", field.name),
+    };
+    let name = &field.name;
+    let snake_name = Inflector::to_snake_case(name);
+    if let Some((_, types)) = sections.exchange_dependent.iter().find(|(title, _)| title == section) {
+        return Some(generate_exchange_dependent_accessor(name, section, &value, types));
+    }
+    Some(if *is_bitflags {
+        format!(
+            r#"    /// `{name}`, refer to "{section}" section of ydDataType.h, unknown bits are kept
+    pub fn {snake_name}(&self) -> {type_name} {{
+        {type_name}::from_bits_retain({value})
+    }}
+"#
+        )
+    } else {
+        format!(
+            r#"    /// `{name}`, refer to "{section}" section of ydDataType.h
+    pub fn {snake_name}(&self) -> Result<{type_name}, UnknownDataTypeValue> {{
+        {type_name}::try_from({value})
+    }}
+"#
+        )
+    })
+}
+
+/// `HedgeFlag` 的值在期货交易所和证券交易所含义不同（`YD_HF_Hedge` 和 `YD_HF_Covered` 都是 3），结构体中没有交易所，
+/// 所以 `hedge_flag()` 返回原始的值，`futures_hedge_flag()`、`stock_hedge_flag()` 按调用方知道的交易所解析
+fn generate_exchange_dependent_accessor(name: &str, section: &str, value: &str, types: &[(String, String)]) -> String {
+    let snake_name = Inflector::to_snake_case(name);
+    let type_names = types.iter().map(|(_, type_name)| format!("`{type_name}`")).collect::<Vec<_>>().join(", ");
+    let mut lines = format!(
+        r#"    /// `{name}`, refer to "{section}" section of ydDataType.h. The same value means different things in different
+    /// exchanges, decode it by the exchange with {type_names}
+    pub fn {snake_name}(&self) -> i32 {{
+        {value}
+    }}
+"#
+    );
+    for (exchange_kind, type_name) in types {
+        lines.push_str(&format!(
+            r#"    /// `{name}` in {exchange_kind} exchanges
+    pub fn {exchange_kind}_{snake_name}(&self) -> Result<{type_name}, UnknownDataTypeValue> {{
+        {type_name}::try_from({value})
+    }}
+"#
+        ));
+    }
+    lines
+}
+
+/// `const YDExchange *m_pExchange;` -> `exchange()`。拷贝到 packet 中的结构体仍然带着这些指针，
+/// api 销毁后它们就失效了，而且用户可以随意构造结构体，所以 accessor 是 unsafe 的，空指针返回 `None`
+fn generate_pointer_accessor(field: &DataStructField) -> Option<String> {
+    let short_name = field.name.strip_prefix("m_p")?;
+    if !field.is_pointer || field.array.is_some() || !field.type_name.starts_with("YD") {
+        return None;
+    }
+    let DataStructField { name, type_name, .. } = field;
+    let snake_name = Inflector::to_snake_case(short_name);
+    Some(format!(
+        r#"    /// `{name}`, `None` if it is null
+    ///
+    /// # Safety
+    ///
+    /// `{name}` must be null or valid, it is valid in structs given by libyd until the api is destroyed
+    pub unsafe fn {snake_name}(&self) -> Option<&{type_name}> {{
+        self.{name}.as_ref()
+    }}
+"#
+    ))
+}
+
+fn generate_class_accessors(class: &DataStructClass, sections: &Sections) -> String {
+    let mut methods: Vec<String> = Vec::new();
+    for field in class.fields.iter() {
+        if HIDDEN_FIELD_PREFIXES.iter().any(|prefix| field.name.starts_with(prefix)) {
+            continue;
+        }
+        methods.extend(
+            generate_string_accessor(field, sections)
+                .or_else(|| generate_data_type_accessor(class, field, sections))
+                .or_else(|| generate_pointer_accessor(field)),
+        );
+    }
+    let mut lines = String::new();
+    if !methods.is_empty() {
+        lines.push_str(&format!("\nimpl {} {{\n{}}}\n", class.name, methods.join("")));
+    }
+    // bindgen 把基类放在 `_base` 字段中，同 YDExtendedApi 一样 Deref 到基类
+    if let Some(base) = &class.base {
+        lines.push_str(&format!(
+            r#"
+impl std::ops::Deref for {name} {{
+    type Target = {base};
+
+    fn deref(&self) -> &{base} {{
+        &self._base
+    }}
+}}
+"#,
+            name = class.name
+        ));
+    }
+    lines
+}
+
+/// 为 ydDataStruct.h 中的类生成只读的 accessor：定长字符串返回 `&str`，ydDataType.h 中的常量返回对应的 enum 或 bitflags，
+/// `m_pXXX` 指针由 unsafe 的方法返回 `Option`。`XXXFilter` 的字段用 -1 和 NULL 表示任意值，不生成 accessor
+pub fn generate_accessors(data_type_header: &str, data_struct_header: &str) -> String {
+    let sections = Sections {
+        data_types: get_data_type_sections(data_type_header),
+        exchange_dependent: get_exchange_dependent_sections(data_type_header),
+        string_typedefs: parse_string_typedefs(data_type_header),
+    };
+    let mut lines = vec![
+        "// read-only accessors of data structs, generated from ydDataType.h and ydDataStruct.h\n\n".to_string(),
+        "use crate::{bindings::*, data_types::*, fixed_str::get_fixed_str};\n".to_string(),
+    ];
+    for class in parse_data_struct_classes(data_struct_header)
+        .iter()
+        .filter(|class| !class.name.ends_with("Filter"))
+    {
+        lines.push(generate_class_accessors(class, &sections));
+    }
+    lines.join("")
+}
@@ -0,0 +1,81 @@
+/// a member variable declared directly in a class of ydDataStruct.h
+pub struct DataStructField {
+    /// `YDInstrumentID`, `int`, `unsigned short`, or `YDExchange` for `const YDExchange *m_pExchange;`
+    pub type_name: String,
+    pub name: String,
+    pub is_pointer: bool,
+    /// `[2][YD_MaxHedgeFlag]` for `m_pCombPositionDef[2][YD_MaxHedgeFlag]`
+    pub array: Option<String>,
+    /// the trailing `//` comment
+    pub comment: Option<String>,
+}
+
+pub struct DataStructClass {
+    pub name: String,
+    /// `YDOrder` for `class YDExtendedOrder: public YDOrder`
+    pub base: Option<String>,
+    pub fields: Vec<DataStructField>,
+}
+
+fn parse_field(line: &str) -> Option<DataStructField> {
+    let (statement, comment) = match line.split_once("//") {
+        Some((statement, comment)) => (statement.trim(), Some(comment.trim().to_string())),
+        None => (line, None),
+    };
+    // 方法声明和 `bool BidOrderFinished,AskOrderFinished;` 这样的多个变量不处理
+    let statement = statement.strip_suffix(';')?.trim();
+    if statement.contains(['(', ',', '=']) {
+        return None;
+    }
+    let statement = statement.trim_start_matches("mutable ").trim_start_matches("const ");
+    let (type_name, declarator) = statement.rsplit_once(|c: char| c.is_whitespace() || c == '*')?;
+    let is_pointer = statement.contains('*');
+    let (name, array) = match declarator.split_once('[') {
+        Some((name, array)) => (name, Some(format!("[{array}"))),
+        None => (declarator, None),
+    };
+    Some(DataStructField {
+        type_name: type_name.trim_end_matches(['*', ' ', '\t']).trim().to_string(),
+        name: name.trim().to_string(),
+        is_pointer,
+        array,
+        comment,
+    })
+}
+
+/// ydDataStruct.h 中每个类直接声明的字段。
+/// 匿名 union/struct 和嵌套类（如 `YDExtendedPosition::CPositionDetail`）里的字段在 bindgen 中不是类的直接字段，不包括在内
+pub fn parse_data_struct_classes(data_struct_header: &str) -> Vec<DataStructClass> {
+    let mut classes: Vec<DataStructClass> = Vec::new();
+    let mut current: Option<DataStructClass> = None;
+    let mut depth = 0;
+    for line in data_struct_header.lines().map(str::trim) {
+        if depth == 0 {
+            if let Some(declaration) = line.strip_prefix("class ") {
+                // `class YDInstrument;` 是前置声明
+                if !declaration.ends_with(';') {
+                    let (name, base) = match declaration.split_once(':') {
+                        Some((name, base)) => (name, Some(base.trim().trim_start_matches("public ").trim().to_string())),
+                        None => (declaration, None),
+                    };
+                    current = Some(DataStructClass {
+                        name: name.trim().to_string(),
+                        base,
+                        fields: Vec::new(),
+                    });
+                }
+            }
+        } else if depth == 1 {
+            if let Some(class) = current.as_mut() {
+                class.fields.extend(parse_field(line));
+            }
+        }
+        let code = line.split("//").next().unwrap();
+        depth += code.matches('{').count();
+        depth -= code.matches('}').count();
+        if depth == 0 && code.contains('}') {
+            classes.extend(current.take());
+        }
+    }
+    classes
+}
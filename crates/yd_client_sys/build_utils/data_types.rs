@@ -0,0 +1,387 @@
+use inflector::Inflector;
+
+/// 这些常量组是按位或组合使用的，生成 bitflags 而不是 enum
+const BITFLAGS_PREFIXES: [&str; 6] = ["YD_AF", "YD_EF", "YD_CIF", "YD_TCF", "YD_MDF", "YD_YQF"];
+
+/// 注释标题不适合直接作为类型名的常量组
+const TYPE_NAME_OVERRIDES: [(&str, &str); 11] = [
+    ("YD_YOF", "OrderFlag"),
+    ("YD_YQF", "QuoteFlag"),
+    ("YD_CHF", "CombHedgeFlag"),
+    ("YD_CPT_DCE", "DceCombPositionType"),
+    ("YD_CPT_GFEX", "GfexCombPositionType"),
+    ("YD_CPT_CZCE", "CzceCombPositionType"),
+    ("YD_CPT_StockOption", "StockOptionCombPositionType"),
+    ("YD_CBT", "CalcBasePriceType"),
+    ("YD_IDT", "IdFromExchangeType"),
+    ("YD_GRPT", "GeneralRiskParamType"),
+    ("YD_MM", "MarginModel"),
+];
+
+struct DataTypeConst {
+    name: String,
+    value: i32,
+    /// 同一个 section 里再细分的注释，例如 "Directions when YDOrderFlag is YD_YOF_CombPosition"
+    subsection: Option<String>,
+    comment: Option<String>,
+}
+
+struct DataTypeGroup {
+    title: String,
+    prefix: String,
+    notes: Vec<String>,
+    consts: Vec<DataTypeConst>,
+}
+
+/// ydDataType.h 里的常量都是按 `// Section` 注释分组的 `const int YD_XX_Name=value;`，
+/// 这里按注释标题和 `YD_XX` 前缀把它们分组
+fn parse_data_type_groups(header: &str) -> Vec<DataTypeGroup> {
+    let mut groups: Vec<DataTypeGroup> = Vec::new();
+    let mut title: Option<String> = None;
+    let mut subsection: Option<String> = None;
+    let mut after_title = false;
+    let mut in_block = false;
+    let mut last_group: Option<usize> = None;
+
+    for line in header.lines().map(str::trim) {
+        if line.is_empty() {
+            after_title = false;
+            in_block = false;
+            subsection = None;
+        } else if let Some(note) = line.strip_prefix("///") {
+            if let Some(index) = last_group {
+                groups[index].notes.push(note.trim().to_string());
+            }
+        } else if let Some(comment) = line.strip_prefix("//") {
+            let comment = comment.trim().to_string();
+            if !in_block && !after_title {
+                title = Some(comment);
+                after_title = true;
+                subsection = None;
+            } else {
+                subsection = Some(comment);
+            }
+        } else if let Some(definition) = line.strip_prefix("const int ") {
+            in_block = true;
+            let (statement, comment) = match definition.split_once("//") {
+                Some((statement, comment)) => (statement, Some(comment.trim().to_string())),
+                None => (definition, None),
+            };
+            let (name, value) = statement
+                .trim_end()
+                .trim_end_matches(';')
+                .split_once('=')
+                .unwrap_or_else(|| panic!("Unexpected constant in ydDataType.h: {line}"));
+            let (name, value) = (name.trim(), value.trim());
+            let segments: Vec<&str> = name.split('_').collect();
+            // `YD_MaxHedgeFlag` 之类的不属于任何常量组
+            if segments.len() < 3 {
+                continue;
+            }
+            let value = match value.strip_prefix("0x") {
+                Some(hex) => i32::from_str_radix(hex, 16),
+                None => value.parse(),
+            }
+            .unwrap_or_else(|_| panic!("Unexpected value in ydDataType.h: {line}"));
+            let title = title.clone().unwrap_or_default();
+            let short_prefix = segments[..2].join("_");
+            let index = match groups
+                .iter()
+                .position(|g| g.title == title && g.prefix.starts_with(&short_prefix))
+            {
+                Some(index) => index,
+                None => {
+                    groups.push(DataTypeGroup {
+                        title,
+                        prefix: short_prefix,
+                        notes: Vec::new(),
+                        consts: Vec::new(),
+                    });
+                    groups.len() - 1
+                }
+            };
+            groups[index].consts.push(DataTypeConst {
+                name: name.to_string(),
+                value,
+                subsection: subsection.clone(),
+                comment,
+            });
+            last_group = Some(index);
+        }
+    }
+
+    // `YD_CPT_DCE_FuturesOffset` 这种常量组的前缀比两段更长，取组内最长的公共前缀
+    for group in groups.iter_mut() {
+        let segments_list: Vec<Vec<&str>> = group.consts.iter().map(|c| c.name.split('_').collect()).collect();
+        let max_len = segments_list.iter().map(|s| s.len() - 1).min().unwrap_or(2);
+        let mut len = 2;
+        while len < max_len && segments_list.iter().all(|s| s[len] == segments_list[0][len]) {
+            len += 1;
+        }
+        group.prefix = segments_list[0][..len].join("_");
+    }
+    groups
+}
+
+fn get_variant_name(group: &DataTypeGroup, data_type_const: &DataTypeConst) -> String {
+    data_type_const.name[group.prefix.len() + 1..].to_string()
+}
+
+fn get_type_name(group: &DataTypeGroup) -> String {
+    TYPE_NAME_OVERRIDES
+        .iter()
+        .find(|(prefix, _)| *prefix == group.prefix)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| {
+            // "YD QuoteFlag, can be bitwise or of following flags"
+            let title = group.title.split(',').next().unwrap_or_default();
+            title.to_pascal_case()
+        })
+}
+
+/// 同一个值在不同的 YDOrderFlag 下含义不同时，按注释拆成不同的类型，例如 `YD_D_Make` 属于 `CombPositionDirection`
+fn get_context_type_name(type_name: &str, subsection: &Option<String>) -> String {
+    let Some(subsection) = subsection else {
+        return type_name.to_string();
+    };
+    if let Some((_, order_flag)) = subsection.split_once("YD_YOF_") {
+        let order_flag: String = order_flag.chars().take_while(|c| c.is_alphanumeric()).collect();
+        if order_flag == "Normal" {
+            type_name.to_string()
+        } else {
+            format!("{order_flag}{type_name}")
+        }
+    } else if subsection.contains("stock exchanges") {
+        format!("Stock{type_name}")
+    } else {
+        type_name.to_string()
+    }
+}
+
+fn get_type_doc(group: &DataTypeGroup, subsection: &Option<String>) -> String {
+    let mut doc = format!("/// {}, see constants start with `{}_` in ydDataType.h\n", group.title, group.prefix);
+    if let Some(subsection) = subsection {
+        doc.push_str(&format!("///\n/// {subsection}\n"));
+    }
+    for note in group.notes.iter() {
+        doc.push_str(&format!("///\n/// {note}\n"));
+    }
+    doc
+}
+
+fn get_const_doc(data_type_const: &DataTypeConst, indent: &str) -> String {
+    match &data_type_const.comment {
+        Some(comment) => format!("{indent}/// `{}`, {comment}\n", data_type_const.name),
+        None => format!("{indent}/// `{}`\n", data_type_const.name),
+    }
+}
+
+fn generate_bitflags(group: &DataTypeGroup) -> String {
+    let type_name = get_type_name(group);
+    let doc = get_type_doc(group, &None)
+        .lines()
+        .map(|line| format!("    {line}\n"))
+        .collect::<String>();
+    let flags = group
+        .consts
+        .iter()
+        .map(|c| {
+            format!(
+                "{}        const {} = {:#x};\n",
+                get_const_doc(c, "        "),
+                get_variant_name(group, c),
+                c.value
+            )
+        })
+        .collect::<String>();
+    format!(
+        r#"
+bitflags::bitflags! {{
+{doc}    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct {type_name}: i32 {{
+{flags}    }}
+}}
+"#
+    )
+}
+
+fn generate_enum(group: &DataTypeGroup, type_name: &str, subsection: &Option<String>, consts: &[&DataTypeConst]) -> String {
+    let doc = get_type_doc(group, subsection);
+    let mut variants = String::new();
+    let mut aliases = String::new();
+    let mut match_arms = String::new();
+    let mut display_arms = String::new();
+    let mut seen: Vec<(i32, String)> = Vec::new();
+    for c in consts.iter() {
+        let variant_name = get_variant_name(group, c);
+        if variant_name == "Count" {
+            // `YD_TRS_Count` 之类的不是合法的值，只是用来开数组的
+            aliases.push_str(&format!("{}    pub const COUNT: usize = {};\n", get_const_doc(c, "    "), c.value));
+            continue;
+        }
+        if let Some((_, existing)) = seen.iter().find(|(value, _)| *value == c.value) {
+            // `YD_MM_NewModelStart` 和 `YD_MM_SPBM` 的值相同
+            aliases.push_str(&format!("{}    pub const {variant_name}: Self = Self::{existing};\n", get_const_doc(c, "    ")));
+            continue;
+        }
+        seen.push((c.value, variant_name.clone()));
+        variants.push_str(&format!("{}    {variant_name} = {},\n", get_const_doc(c, "    "), c.value));
+        match_arms.push_str(&format!("            {} => Ok(Self::{variant_name}),\n", c.value));
+        display_arms.push_str(&format!("            Self::{variant_name} => \"{variant_name}\",\n"));
+    }
+    let mut lines = format!(
+        r#"
+{doc}#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum {type_name} {{
+{variants}}}
+
+impl TryFrom<i32> for {type_name} {{
+    type Error = UnknownDataTypeValue;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {{
+        match value {{
+{match_arms}            _ => Err(UnknownDataTypeValue {{ type_name: "{type_name}", value }}),
+        }}
+    }}
+}}
+
+impl From<{type_name}> for i32 {{
+    fn from(value: {type_name}) -> Self {{
+        value as i32
+    }}
+}}
+
+impl fmt::Display for {type_name} {{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {{
+        f.write_str(match self {{
+{display_arms}        }})
+    }}
+}}
+"#
+    );
+    // 放在 `char` 字段里的值，例如 `YDInputOrder::Direction`，c_char 在不同平台上可能有符号也可能无符号
+    if seen.iter().all(|(value, _)| (0..=127).contains(value)) {
+        lines.push_str(&format!(
+            r#"
+impl TryFrom<c_char> for {type_name} {{
+    type Error = UnknownDataTypeValue;
+
+    fn try_from(value: c_char) -> Result<Self, Self::Error> {{
+        Self::try_from(value as i32)
+    }}
+}}
+
+impl From<{type_name}> for c_char {{
+    fn from(value: {type_name}) -> Self {{
+        value as c_char
+    }}
+}}
+"#
+        ));
+    }
+    if !aliases.is_empty() {
+        lines.push_str(&format!("\nimpl {type_name} {{\n{aliases}}}\n"));
+    }
+    lines
+}
+
+/// subsections of a group whose values overlap, each of them becomes an enum, `None` if the group is a single enum
+fn get_split_subsections(group: &DataTypeGroup) -> Option<Vec<&Option<String>>> {
+    let mut subsections: Vec<&Option<String>> = Vec::new();
+    for c in group.consts.iter().filter(|c| get_variant_name(group, c) != "Count") {
+        if !subsections.contains(&&c.subsection) {
+            subsections.push(&c.subsection);
+        }
+    }
+    let mut values: Vec<i32> = group.consts.iter().map(|c| c.value).collect();
+    values.sort();
+    values.dedup();
+    let has_overloaded_values = values.len() < group.consts.len();
+    (subsections.len() > 1 && has_overloaded_values).then_some(subsections)
+}
+
+fn generate_enums(group: &DataTypeGroup) -> String {
+    let type_name = get_type_name(group);
+    if let Some(subsections) = get_split_subsections(group) {
+        let mut lines = String::new();
+        let mut type_names: Vec<String> = Vec::new();
+        for subsection in subsections {
+            let context_type_name = get_context_type_name(&type_name, subsection);
+            assert!(
+                !type_names.contains(&context_type_name),
+                "Duplicated type name {context_type_name} in ydDataType.h section {}",
+                group.title
+            );
+            let consts: Vec<&DataTypeConst> = group.consts.iter().filter(|c| &c.subsection == subsection).collect();
+            lines.push_str(&generate_enum(group, &context_type_name, subsection, &consts));
+            type_names.push(context_type_name);
+        }
+        lines
+    } else {
+        let consts: Vec<&DataTypeConst> = group.consts.iter().collect();
+        generate_enum(group, &type_name, &None, &consts)
+    }
+}
+
+/// 按交易所拆成多个 enum 的分组，如 "Hedge Flag" -> [("futures", "HedgeFlag"), ("stock", "StockHedgeFlag")]，
+/// 名字取自 "following hedge flags are used in stock exchanges" 这样的注释中 `exchanges` 前面的词
+pub fn get_exchange_dependent_sections(header: &str) -> Vec<(String, Vec<(String, String)>)> {
+    parse_data_type_groups(header)
+        .iter()
+        .filter_map(|group| {
+            let type_name = get_type_name(group);
+            let types: Option<Vec<(String, String)>> = get_split_subsections(group)?
+                .into_iter()
+                .map(|subsection| {
+                    let words: Vec<&str> = subsection.as_deref()?.split_whitespace().collect();
+                    let index = words.iter().position(|word| *word == "exchanges")?;
+                    let exchange_kind = words.get(index.checked_sub(1)?)?.to_lowercase();
+                    Some((exchange_kind, get_context_type_name(&type_name, subsection)))
+                })
+                .collect();
+            Some((group.title.clone(), types?))
+        })
+        .collect()
+}
+
+/// ydDataType.h 中的注释标题（如 "Order Status"）-> (生成的类型名, 是否为 bitflags)，
+/// 分组拆成多个 enum 的，如 "Direction"，对应一般情况下使用的那个
+pub fn get_data_type_sections(header: &str) -> Vec<(String, String, bool)> {
+    parse_data_type_groups(header)
+        .iter()
+        .map(|group| (group.title.clone(), get_type_name(group), BITFLAGS_PREFIXES.contains(&group.prefix.as_str())))
+        .collect()
+}
+
+/// 为 ydDataType.h 中的每组常量生成带类型的 enum 或 bitflags，以免在业务代码里直接用 i32 常量
+pub fn generate_data_types(header: &str) -> String {
+    let mut lines = vec![format!(
+        r#"use std::{{fmt, os::raw::c_char}};
+
+/// the value is not one of the constants in ydDataType.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownDataTypeValue {{
+    pub type_name: &'static str,
+    pub value: i32,
+}}
+
+impl fmt::Display for UnknownDataTypeValue {{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {{
+        write!(f, "unknown {{}} value: {{}}", self.type_name, self.value)
+    }}
+}}
+
+impl std::error::Error for UnknownDataTypeValue {{}}
+"#
+    )];
+    for group in parse_data_type_groups(header).iter() {
+        lines.push(format!("\n/* Generated by generate_data_types from {} */", group.prefix));
+        if BITFLAGS_PREFIXES.contains(&group.prefix.as_str()) {
+            lines.push(generate_bitflags(group));
+        } else {
+            lines.push(generate_enums(group));
+        }
+    }
+    lines.join("")
+}
@@ -1,5 +1,6 @@
 use clang::{Entity, EntityKind};
 use inflector::Inflector;
+use std::{env, path::PathBuf};
 
 pub fn format_enum_name(name: &str) -> String {
     let clean_name = if name.starts_with('~') {
@@ -25,3 +26,115 @@ pub fn get_full_name_of_entity(e: &Entity) -> String {
     v.reverse();
     v.iter().filter(|name| !name.is_empty()).cloned().collect::<Vec<_>>().join("_")
 }
+
+lazy_static! {
+    static ref OVERLOAD_NAME_OVERRIDES: Vec<OverloadNameOverride> = load_overload_name_overrides();
+}
+
+/// a row of overload_names.csv
+struct OverloadNameOverride {
+    record: String,
+    method: String,
+    parameter_types: Vec<String>,
+    rust_name: String,
+}
+
+/// overloads whose derived names are not good enough, or collide, are listed in build_utils/overload_names.csv,
+/// `YD_CLIENT_SYS_OVERLOAD_NAMES` replaces it with another file, e.g. for an SDK with new overloads
+fn load_overload_name_overrides() -> Vec<OverloadNameOverride> {
+    println!("cargo:rerun-if-env-changed=YD_CLIENT_SYS_OVERLOAD_NAMES");
+    let path = env::var_os("YD_CLIENT_SYS_OVERLOAD_NAMES").map(PathBuf::from).unwrap_or_else(|| {
+        PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("build_utils").join("overload_names.csv")
+    });
+    println!("cargo:rerun-if-changed={}", path.display());
+    let mut reader = csv::Reader::from_path(&path)
+        .unwrap_or_else(|error| panic!("Unable to read {}: {error}", path.display()));
+    reader
+        .records()
+        .map(|record| {
+            let record = record.unwrap_or_else(|error| panic!("Unable to parse {}: {error}", path.display()));
+            OverloadNameOverride {
+                record: record[0].to_string(),
+                method: record[1].to_string(),
+                parameter_types: record[2].split_whitespace().map(str::to_string).collect(),
+                rust_name: record[3].to_string(),
+            }
+        })
+        .collect()
+}
+
+/// parameter type as a name: `const YDExtendedPosition *` -> `extended_position`, `unsigned int` -> `unsigned_int`,
+/// `const YDExtendedOrder *[]` -> `extended_order`. Types don't change with parameter names between SDK versions
+fn get_type_name(type_name: &str) -> String {
+    let type_name = type_name
+        .split(|c: char| c.is_whitespace() || matches!(c, '*' | '&' | '[' | ']'))
+        .filter(|word| !word.is_empty() && !matches!(*word, "const" | "struct" | "class"))
+        .collect::<Vec<_>>()
+        .join("_");
+    type_name.strip_prefix("YD").unwrap_or(&type_name).to_snake_case()
+}
+
+fn get_parameter_types(method: &Entity) -> Vec<String> {
+    method
+        .get_arguments()
+        .unwrap_or_default()
+        .iter()
+        .map(|argument| get_type_name(&argument.get_type().map(|t| t.get_display_name()).unwrap_or_default()))
+        .collect()
+}
+
+/// snake case name of an overload derived from parameter types, `overloads` contains `parameter_types` itself
+fn derive_overload_name(snake_name: &str, parameter_types: &[String], overloads: &[Vec<String>]) -> String {
+    if overloads.len() <= 1 {
+        return snake_name.to_string();
+    }
+    if overloads.iter().any(|overload| overload.first() != parameter_types.first()) {
+        return format!("{snake_name}_by_{}", parameter_types.first().cloned().unwrap_or_default());
+    }
+    let fewest = overloads.iter().map(Vec::len).min().unwrap_or_default();
+    if parameter_types.len() == fewest {
+        snake_name.to_string()
+    } else {
+        format!("{snake_name}_with_{}", parameter_types[fewest..].join("_and_"))
+    }
+}
+
+/// snake case name of a method, overloads are named after their parameter types unless listed in overload_names.csv
+/// - different first parameters: `getMarginModel(const YDInstrument *pInstrument,...)` -> `get_margin_model_by_instrument`,
+///   `getMarginPerLot(const YDExtendedPosition *pPosition,...)` -> `get_margin_per_lot_by_extended_position`
+/// - extra parameters: `findOrders(const YDOrderFilter *pFilter)` keeps the plain name, the other overloads get
+///   `_with_` and the extra types, like `_with_int`
+///
+/// Names that collide, e.g. `getOrder(int orderRef,...)` and `getOrder(int orderSysID,...)`, must be listed in the file
+pub fn get_method_name(entity: &Entity) -> String {
+    let name = entity.get_name().unwrap();
+    let snake_name = name.to_snake_case();
+    let record_name = entity.get_semantic_parent().and_then(|parent| parent.get_name()).unwrap_or_default();
+    let overloads: Vec<Vec<String>> = entity
+        .get_lexical_parent()
+        .map(|parent| {
+            parent
+                .get_children()
+                .iter()
+                .filter(|sibling| sibling.get_kind() == entity.get_kind() && sibling.get_name().as_deref() == Some(&name))
+                .map(get_parameter_types)
+                .collect()
+        })
+        .unwrap_or_default();
+    let rust_name = |parameter_types: &[String]| {
+        OVERLOAD_NAME_OVERRIDES
+            .iter()
+            .find(|row| row.record == record_name && row.method == name && row.parameter_types == parameter_types)
+            .map(|row| row.rust_name.clone())
+            .unwrap_or_else(|| derive_overload_name(&snake_name, parameter_types, &overloads))
+    };
+    let parameter_types = get_parameter_types(entity);
+    let method_name = rust_name(&parameter_types);
+    let same_names = overloads.iter().filter(|overload| rust_name(overload) == method_name).count();
+    assert!(
+        same_names <= 1,
+        "Overloads of {record_name}::{name} share the name {method_name}, add a row for ({}) to overload_names.csv",
+        parameter_types.join(" ")
+    );
+    method_name
+}
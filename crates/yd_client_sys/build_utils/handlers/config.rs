@@ -10,8 +10,8 @@ pub struct HandlerConfigs {
     pub record_flavor: RecordFlavor,
     pub record_name: String,
     pub life_time: String,
-    /// modified by children, to let parent know if any child use a life time parameter
-    pub life_time_on_children: bool,
+    /// modified by children, to let parent know if any child is a struct copied from libyd, which may contain raw pointers
+    pub snapshot_on_children: bool,
     pub prefer_pointer: bool,
     /// the index of this child in its parent
     pub index: usize,
@@ -27,7 +27,7 @@ impl Default for HandlerConfigs {
             parameter_flavor: ParameterFlavor::None,
             record_flavor: RecordFlavor::None,
             life_time: "".to_string(),
-            life_time_on_children: false,
+            snapshot_on_children: false,
             prefer_pointer: false,
             record_name: "WarningRecordNameUnfilled".to_string(),
             index: 0,
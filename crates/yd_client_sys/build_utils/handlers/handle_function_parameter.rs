@@ -40,6 +40,7 @@ pub fn insert_function_parameter_handlers(handlers: &mut HandlerMap) {
         TypeKind::Pointer,
         TypeKind::Typedef,
         TypeKind::UInt,
+        TypeKind::LongLong,
         TypeKind::ULongLong,
         TypeKind::Enum,
     ];
@@ -68,6 +69,11 @@ pub fn handle_function_parameter(
     );
 
     let parameter_str = match entity_type.get_kind() {
+        TypeKind::Pointer
+            if matches!(configs.parameter_flavor, ParameterFlavor::RustStruct | ParameterFlavor::SpiFn) =>
+        {
+            get_snapshot_parameter(entity, &entity_name, &entity_type, configs)
+        }
         TypeKind::Pointer => {
             let parameter = get_pointer_parameter(&entity_name, &entity_type, configs);
             match &configs.parameter_flavor {
@@ -90,6 +96,10 @@ pub fn handle_function_parameter(
             ParameterFlavor::UnsafeCheck => "".to_string(),
             _ => format_parameter(&entity_name, "f64", &configs.parameter_flavor),
         },
+        TypeKind::LongLong => match &configs.parameter_flavor {
+            ParameterFlavor::UnsafeCheck => "".to_string(),
+            _ => format_parameter(&entity_name, "i64", &configs.parameter_flavor),
+        },
         TypeKind::ULongLong => match &configs.parameter_flavor {
             ParameterFlavor::UnsafeCheck => "".to_string(),
             _ => format_parameter(&entity_name, "u64", &configs.parameter_flavor),
@@ -110,14 +120,19 @@ pub fn handle_function_parameter(
                 format_parameter(&entity_name, &rust_type, &configs.parameter_flavor)
             }
         },
-        TypeKind::IncompleteArray => match &configs.parameter_flavor {
-            ParameterFlavor::UnsafeCheck => "".to_string(),
-            ParameterFlavor::MethodCallParam => match &configs.record_flavor {
-                RecordFlavor::SPI => format!("{}", &entity_name),
-                _ => format!("{}.iter().map(|cs| cs.as_ptr()).collect::<Vec<_>>().as_mut_ptr() as *mut *mut i8", &entity_name),
-            },
-            _ => format_parameter(&entity_name, "Vec<std::ffi::CString>", &configs.parameter_flavor),
-        },        
+        TypeKind::IncompleteArray
+            if matches!(configs.parameter_flavor, ParameterFlavor::RustStruct | ParameterFlavor::SpiFn) =>
+        {
+            get_snapshot_array_parameter(entity, &entity_name, &configs.parameter_flavor)
+        }
+        TypeKind::IncompleteArray => {
+            let parameter = get_incomplete_array_parameter(&entity_name, &entity_type, configs);
+            match &configs.parameter_flavor {
+                ParameterFlavor::UnsafeCheck => "".to_string(),
+                ParameterFlavor::MethodCallParam => parameter,
+                _ => format_parameter(&entity_name, &parameter, &configs.parameter_flavor),
+            }
+        }
         _ => {
             println!("handle_function_parameter not handling {:?}", entity_type);
             panic!("");
@@ -166,6 +181,70 @@ fn format_parameter(name: &str, parameter: &str, flavor: &ParameterFlavor) -> St
     }
 }
 
+/// catalog objects and their refs, see `getExchange`, `getProduct`, `getInstrument`... of `YDApi`
+const CATALOG_REF_FIELDS: [(&str, &str); 5] = [
+    ("YDExchange", "ExchangeRef"),
+    ("YDProduct", "ProductRef"),
+    ("YDInstrument", "InstrumentRef"),
+    ("YDCombPositionDef", "CombPositionRef"),
+    ("YDAccount", "AccountRef"),
+];
+
+/// callbacks whose catalog object is the event itself, so it is copied like other events
+const CATALOG_EVENT_CALLBACKS: [&str; 1] = ["notifyAccount"];
+
+/// listener arrays have no length, `groupMaxOrderRef` is indexed by `OrderGroupID`, which is 0 to 63
+const LISTENER_ARRAY_LENGTHS: [(&str, usize); 1] = [("groupMaxOrderRef", 64)];
+
+/// Packets of listener callbacks must not borrow from libyd, because they are consumed after the callback returns.
+/// Events like `YDOrder` are copied by value, and catalog objects like `YDInstrument` are replaced by their refs,
+/// e.g. `pInstrument` -> `instrument_ref: i32`, which can be resolved by `YDApi::get_instrument`.
+fn get_snapshot_parameter(entity: &Entity, name: &str, entity_type: &Type, configs: &mut HandlerConfigs) -> String {
+    let pointee_type = entity_type.get_pointee_type().unwrap();
+    let type_name = pointee_type
+        .get_declaration()
+        .map(|decl| get_full_name_of_entity(&decl))
+        .unwrap_or_else(|| panic!("Unhandled pointer in listener: {name} {:?}", pointee_type));
+    let callback_name = entity.get_semantic_parent().and_then(|parent| parent.get_name()).unwrap_or_default();
+    let field_name = name.trim_start_matches("p_");
+    let catalog_ref = CATALOG_REF_FIELDS
+        .iter()
+        .find(|(catalog_type, _)| *catalog_type == type_name)
+        .filter(|_| !CATALOG_EVENT_CALLBACKS.contains(&callback_name.as_str()));
+    match (catalog_ref, &configs.parameter_flavor) {
+        (Some(_), ParameterFlavor::RustStruct) => {
+            format!("{indent}pub {field_name}_ref: i32", indent = *INDENT)
+        }
+        (Some((_, ref_field)), _) => {
+            format!("{indent}{indent}{indent}{field_name}_ref: {name}.{ref_field}", indent = *INDENT)
+        }
+        (None, ParameterFlavor::RustStruct) => {
+            // copied structs may still have pointers to catalog objects, like `YDMarketData::m_pInstrument`
+            configs.snapshot_on_children = true;
+            format!("{indent}pub {field_name}: {type_name}", indent = *INDENT)
+        }
+        (None, _) => format!("{indent}{indent}{indent}{field_name}: *{name}", indent = *INDENT),
+    }
+}
+
+fn get_snapshot_array_parameter(entity: &Entity, name: &str, flavor: &ParameterFlavor) -> String {
+    let raw_name = entity.get_name().unwrap();
+    let length = LISTENER_ARRAY_LENGTHS
+        .iter()
+        .find(|(array_name, _)| *array_name == raw_name)
+        .map(|(_, length)| *length)
+        .unwrap_or_else(|| panic!("Unknown length of listener array {raw_name}"));
+    match flavor {
+        ParameterFlavor::RustStruct => {
+            format!("{indent}pub {name}: [std::os::raw::c_int; {length}]", indent = *INDENT)
+        }
+        _ => format!(
+            "{indent}{indent}{indent}{name}: unsafe {{ std::ptr::read({name} as *const [std::os::raw::c_int; {length}]) }}",
+            indent = *INDENT
+        ),
+    }
+}
+
 fn get_pointer_parameter(name: &str, entity_type: &Type, configs: &mut HandlerConfigs) -> String {
     let pointee_type = entity_type.get_pointee_type().unwrap();
     let flavor = &configs.parameter_flavor;
@@ -173,14 +252,18 @@ fn get_pointer_parameter(name: &str, entity_type: &Type, configs: &mut HandlerCo
     match pointee_type.get_kind() {
         TypeKind::CharS => match flavor {
             ParameterFlavor::MethodCallParam => format!("{}.as_ptr()", name),
-            ParameterFlavor::Rust | ParameterFlavor::RustStruct => "std::ffi::CString".to_string(),
+            ParameterFlavor::Rust if configs.prefer_pointer => "*const std::os::raw::c_char".to_string(),
+            // API 方法接收 &str，在调用 C++ 前转换为 CString，见 handle_function_prototype
+            ParameterFlavor::Rust => "&str".to_string(),
+            ParameterFlavor::RustStruct => "std::ffi::CString".to_string(),
             ParameterFlavor::SpiFn => "*const std::os::raw::c_char".to_string(),
             ParameterFlavor::UnsafeCheck => "/* No checking 2 */".to_string(),
             ParameterFlavor::None => "/* char* */".to_string(),
         },
         TypeKind::UChar => match flavor {
-            ParameterFlavor::MethodCallParam => format!("{} as *mut ::std::os::raw::c_uchar", name),
-            ParameterFlavor::Rust | ParameterFlavor::RustStruct => "u8".to_string(),
+            ParameterFlavor::MethodCallParam => format!("{}.as_mut_ptr()", name),
+            ParameterFlavor::Rust if configs.prefer_pointer => "*mut std::os::raw::c_uchar".to_string(),
+            ParameterFlavor::Rust | ParameterFlavor::RustStruct => "&mut [std::os::raw::c_uchar]".to_string(),
             ParameterFlavor::SpiFn => "*const std::os::raw::c_uchar".to_string(),
             ParameterFlavor::UnsafeCheck => "/* No checking 3 */".to_string(),
             ParameterFlavor::None => "/* unsigned char* */".to_string(),
@@ -203,19 +286,25 @@ fn get_pointer_parameter(name: &str, entity_type: &Type, configs: &mut HandlerCo
         TypeKind::Record => {
             let decl = pointee_type.get_declaration().unwrap();
             let entity_name = get_full_name_of_entity(&decl);
+            let mutability = if pointee_type.is_const_qualified() { "" } else { "mut " };
             console_debug!("TypeKind::Record {:?} {:?} {:?}", decl, entity_name, flavor,);
             match flavor {
-                ParameterFlavor::MethodCallParam => format!("&mut *{}", name),
+                ParameterFlavor::MethodCallParam => {
+                    if configs.prefer_pointer {
+                        format!("&{mutability}*{name}")
+                    } else {
+                        format!("{name}")
+                    }
+                }
                 ParameterFlavor::Rust  => {
-                    configs.life_time_on_children = true;
                     if configs.prefer_pointer {
-                        format!("*{}mut {}", configs.life_time, entity_name)
+                        let pointer_mutability = if pointee_type.is_const_qualified() { "const" } else { "mut" };
+                        format!("*{pointer_mutability} {entity_name}")
                     } else {
-                        format!("&{}mut {}", configs.life_time, entity_name)
+                        format!("&{}{mutability}{entity_name}", configs.life_time)
                     }
                 }
                 ParameterFlavor::RustStruct => {
-                    configs.life_time_on_children = true;
                     format!("&{}{}", configs.life_time, entity_name)
                 }
                 ParameterFlavor::SpiFn => format!("{}", entity_name),
@@ -224,20 +313,33 @@ fn get_pointer_parameter(name: &str, entity_type: &Type, configs: &mut HandlerCo
             }
         }
         TypeKind::Elaborated => {
-            let pointee_name = pointee_type.get_display_name();
+            let pointee_name = pointee_type
+                .get_declaration()
+                .map(|decl| get_full_name_of_entity(&decl))
+                .unwrap_or_else(|| pointee_type.get_display_name());
+            let is_const = pointee_type.is_const_qualified();
             match flavor {
                 ParameterFlavor::MethodCallParam => {
-                    if entity_type.is_const_qualified() {
+                    if !configs.prefer_pointer {
                         format!("{}", name)
+                    } else if is_const {
+                        format!("&*{}", name)
+                    } else {
+                        format!("&mut *{}", name)
+                    }
+                }
+                ParameterFlavor::Rust if configs.prefer_pointer => {
+                    if is_const {
+                        format!("*const {}", pointee_name)
                     } else {
-                        format!("{} as *mut", name)
+                        format!("*mut {}", pointee_name)
                     }
                 }
                 ParameterFlavor::Rust | ParameterFlavor::RustStruct => {
-                    if entity_type.is_const_qualified() {
-                        format!("&{}", pointee_name)
+                    if is_const {
+                        format!("&{}{}", configs.life_time, pointee_name)
                     } else {
-                        format!("&mut {}", pointee_name)
+                        format!("&{}mut {}", configs.life_time, pointee_name)
                     }
                 }
                 ParameterFlavor::SpiFn => format!("&{}", pointee_name),
@@ -246,6 +348,30 @@ fn get_pointer_parameter(name: &str, entity_type: &Type, configs: &mut HandlerCo
             ParameterFlavor::None => format!("/* {} */", pointee_name),
             }
         }
+        TypeKind::Int | TypeKind::UInt => {
+            // `const int *combTypes` is an input list, `unsigned *pSessionID` is an output parameter
+            let primitive = if pointee_type.get_kind() == TypeKind::Int {
+                "std::os::raw::c_int"
+            } else {
+                "std::os::raw::c_uint"
+            };
+            let is_const = pointee_type.is_const_qualified();
+            match flavor {
+                ParameterFlavor::MethodCallParam => format!("{}", name),
+                ParameterFlavor::Rust | ParameterFlavor::RustStruct => {
+                    if is_const {
+                        format!("*const {}", primitive)
+                    } else if configs.prefer_pointer {
+                        format!("*mut {}", primitive)
+                    } else {
+                        format!("&mut {}", primitive)
+                    }
+                }
+                ParameterFlavor::SpiFn => format!("{}", name),
+                ParameterFlavor::UnsafeCheck => format!("/* No checking 15 {} */", primitive),
+                ParameterFlavor::None => format!("/* {} */", primitive),
+            }
+        }
         _ => {
             if let Some(decl) = pointee_type.get_declaration() {
                 let entity_name = get_full_name_of_entity(&decl);
@@ -258,8 +384,7 @@ fn get_pointer_parameter(name: &str, entity_type: &Type, configs: &mut HandlerCo
                         }
                     },
                     ParameterFlavor::Rust | ParameterFlavor::RustStruct => {
-                        configs.life_time_on_children = true;
-                        if configs.prefer_pointer {
+                            if configs.prefer_pointer {
                             format!("*mut {}{}", configs.life_time, entity_name)
                         } else {
                             format!("&{}{}", configs.life_time, entity_name)
@@ -276,6 +401,48 @@ fn get_pointer_parameter(name: &str, entity_type: &Type, configs: &mut HandlerCo
     }
 }
 
+/// `YDInputOrder inputOrders[]`, `const YDInstrument *instruments[]`, `double legMargins[]`...
+/// are slices on the rust side of API, and raw pointers in v-table and SPI
+fn get_incomplete_array_parameter(name: &str, entity_type: &Type, configs: &mut HandlerConfigs) -> String {
+    let element_type = entity_type.get_element_type().unwrap();
+    let element_name = match element_type.get_kind() {
+        TypeKind::Int => "std::os::raw::c_int".to_string(),
+        TypeKind::Double => "f64".to_string(),
+        TypeKind::Pointer => {
+            let decl = element_type.get_pointee_type().unwrap().get_declaration().unwrap();
+            format!("*const {}", get_full_name_of_entity(&decl))
+        }
+        _ => {
+            let decl = element_type
+                .get_declaration()
+                .unwrap_or_else(|| panic!("Unhandled array element type: {:?}", element_type));
+            get_full_name_of_entity(&decl)
+        }
+    };
+    let is_const = element_type.is_const_qualified();
+    let use_pointer = configs.prefer_pointer || matches!(configs.record_flavor, RecordFlavor::SPI);
+    match &configs.parameter_flavor {
+        ParameterFlavor::MethodCallParam => {
+            if use_pointer {
+                format!("{}", name)
+            } else if is_const {
+                format!("{}.as_ptr()", name)
+            } else {
+                format!("{}.as_mut_ptr()", name)
+            }
+        }
+        ParameterFlavor::Rust | ParameterFlavor::RustStruct => match (use_pointer, is_const) {
+            (true, true) => format!("*const {}", element_name),
+            (true, false) => format!("*mut {}", element_name),
+            (false, true) => format!("&[{}]", element_name),
+            (false, false) => format!("&mut [{}]", element_name),
+        },
+        ParameterFlavor::SpiFn => format!("{}", name),
+        ParameterFlavor::UnsafeCheck => format!("/* No checking 16 {} */", element_name),
+        ParameterFlavor::None => format!("/* {}[] */", element_name),
+    }
+}
+
 fn get_typedef_parameter(name: &str, entity_type: &Type, configs: &mut HandlerConfigs) -> String {
     let underlying_type = entity_type
         .get_declaration()
@@ -1,6 +1,8 @@
 use crate::build_utils::{
-    config::HandlerConfigs, format_name::format_enum_name,
-    handle_function_parameter::ParameterFlavor, process_children, HandlerMap,
+    config::HandlerConfigs,
+    format_name::{format_enum_name, get_full_name_of_entity, get_method_name},
+    handle_function_parameter::ParameterFlavor,
+    process_children, HandlerMap,
 };
 use clang::*;
 use inflector::Inflector;
@@ -22,6 +24,8 @@ pub enum MethodFlavor {
     /// method is in a trait
     SpiTrait,
     ApiTrait,
+    /// method is a function pointer field in v-table of C++ API class
+    ApiVTableStruct,
     StaticTable,
     OutputEnum,
     OutputEnumStruct,
@@ -36,6 +40,9 @@ pub fn handle_function_prototype(
     handlers: &HandlerMap,
     configs: &mut HandlerConfigs,
 ) -> Vec<String> {
+    if entity.get_kind() == EntityKind::Destructor {
+        return handle_destructor(configs);
+    }
     let raw_camel_case_name = entity.get_name().unwrap();
     let record_name = configs.record_name.clone();
     let sibling_index = find_previous_sibling_index(entity, configs);
@@ -44,16 +51,19 @@ pub fn handle_function_prototype(
     } else {
         "".to_string()
     };
-    let snake_fn_name = format!(
+    // bindgen 给 v-table 中的重载加数字后缀，如 `YDExtendedApi_getOrder1`，rust 侧的名字则根据参数生成
+    let numbered_snake_fn_name = format!(
         "{}{method_reload_suffix}",
         Inflector::to_snake_case(&raw_camel_case_name)
     );
-    let camel_case_name = Inflector::to_camel_case(&snake_fn_name).replace("Id", "ID");
+    let camel_case_name = Inflector::to_camel_case(&numbered_snake_fn_name).replace("Id", "ID");
+    let snake_fn_name = get_method_name(entity);
 
-    let enum_name = format!(
-        "{}{method_reload_suffix}",
+    let enum_name = if snake_fn_name == Inflector::to_snake_case(&raw_camel_case_name) {
         format_enum_name(&raw_camel_case_name)
-    );
+    } else {
+        format_enum_name(&snake_fn_name)
+    };
     let packet_name_prefix = format!("{record_name}{enum_name}");
 
     let mut lines: Vec<String> = vec![];
@@ -84,10 +94,6 @@ pub fn handle_function_prototype(
             lines.push(format!(") {{}}\n"));
         }
         MethodFlavor::ApiTrait => {
-            if raw_camel_case_name.starts_with("~") {
-                // TODO: 在别处处理类的析构
-                return lines;
-            }
             if raw_camel_case_name == "start" {
                 /*
                  * 使用我们包装过的 Trait，而不是原生的 Listener 类
@@ -95,32 +101,122 @@ pub fn handle_function_prototype(
                  */
                 lines.push(format!(
                     r#"
-    pub fn start(&mut self, p_listener: *const dyn YDListenerTrait) -> bool {{
-        let p_listener = Box::into_raw(Box::new((&YD_LISTENER_VTABLE, p_listener)));
+    /// libyd owns the listener until `notifyAfterApiDestroy`, then it is released.
+    /// Only `YDApiHandle` starts the api, otherwise it wouldn't call `startDestroy` on drop
+    pub(crate) fn start(&mut self, listener: Box<dyn for<'a> YDListenerTrait<'a> + Send>, on_destroy_phase: Option<Box<dyn Fn(ApiDestroyPhase) + Send>>, on_server_restarted: Option<Box<dyn Fn() + Send>>) -> bool {{
+        let p_listener = YDListenerFat::into_raw(listener);
         unsafe {{
-            ((*(*self).vtable_).YDApi_start)(self as *mut YDApi, p_listener as *mut YDListener)
+            (*p_listener).on_destroy_phase = on_destroy_phase;
+            (*p_listener).on_server_restarted = on_server_restarted;
+        }}
+        let started = unsafe {{
+            (self.vtable().{record_name}_start)(self as *mut {record_name}, p_listener as *mut YDListener)
+        }};
+        if !started {{
+            unsafe {{ YDListenerFat::release(p_listener) }};
         }}
+        started
     }}
 "#,));
                 return lines;
             }
-            if raw_camel_case_name.contains("Multi") {
-                /*
-                 * 跳过 `insert_multi_orders cancel_multi_orders insert_multi_quotes cancel_multi_quotes` 直到了解正确的类型转换方式
-                 */
+            if raw_camel_case_name == "startExtended" {
+                // 同 start，另外注册 YDExtendedListener 的回调，它没有 notifyAfterApiDestroy，所以随 YDListener 一起释放
+                lines.push(format!(
+                    r#"
+    /// libyd owns both listeners until `notifyAfterApiDestroy`, then they are released, see `start`
+    pub(crate) fn start_extended(&mut self, listener: Box<dyn for<'a> YDListenerTrait<'a> + Send>, extended_listener: Box<dyn for<'a> YDExtendedListenerTrait<'a> + Send>, on_destroy_phase: Option<Box<dyn Fn(ApiDestroyPhase) + Send>>, on_server_restarted: Option<Box<dyn Fn() + Send>>) -> bool {{
+        let p_listener = YDListenerFat::into_raw(listener);
+        let p_extended_listener = YDExtendedListenerFat::into_raw(extended_listener);
+        unsafe {{
+            (*p_listener).on_destroy_phase = on_destroy_phase;
+            (*p_listener).on_server_restarted = on_server_restarted;
+            (*p_listener).on_release = Some(Box::new(move || YDExtendedListenerFat::release(p_extended_listener)));
+        }}
+        let started = unsafe {{
+            (self.vtable().{record_name}_startExtended)(self as *mut {record_name}, p_listener as *mut YDListener, p_extended_listener as *mut YDExtendedListener)
+        }};
+        if !started {{
+            unsafe {{ YDListenerFat::release(p_listener) }};
+        }}
+        started
+    }}
+"#,));
+                return lines;
+            }
+            if raw_camel_case_name == "writeLog" {
+                // writeLog(const char *format,...) 是 printf 风格的，用户文本只能作为 "%s" 的参数，否则其中的 % 会读取不存在的参数
                 lines.push(format!(
-                    "{}// {snake_fn_name} // Ignored (MethodFlavor::ApiTrait)\n",
-                    *INDENT
+                    r#"
+    /// write `message` to the log of libyd, it is passed as the argument of `"%s"`, so `%` in it is not a format directive
+    pub fn write_log(&mut self, message: &str) -> Result<(), std::ffi::NulError> {{
+        let message = std::ffi::CString::new(message)?;
+        unsafe {{
+            (self.vtable().{record_name}_writeLog)(self as *mut {record_name}, b"%s\0".as_ptr() as *const std::os::raw::c_char, message.as_ptr())
+        }}
+        Ok(())
+    }}
+"#,));
+                return lines;
+            }
+            if raw_camel_case_name.contains("Multi") {
+                // `insert_multi_orders cancel_multi_orders insert_multi_quotes cancel_multi_quotes` 用 slice 传入，并在调用 C++ 前检查长度
+                lines.push(handle_multi_method(
+                    entity,
+                    &record_name,
+                    &snake_fn_name,
+                    &format!("{record_name}_{camel_case_name}"),
                 ));
                 return lines;
             }
-            lines.push(format!("{}pub fn {snake_fn_name}(&mut self", *INDENT));
+            if raw_camel_case_name == "cancelOrder" || raw_camel_case_name == "cancelQuote" {
+                // pAccount 可以为 NULL，交易员不需要传入账户
+                lines.push(handle_cancel_method(
+                    entity,
+                    &record_name,
+                    &snake_fn_name,
+                    &format!("{record_name}_{camel_case_name}"),
+                ));
+                return lines;
+            }
+            // startDestroy 和 start 一样只能由 YDApiHandle 调用，否则 handle 不知道 api 已经开始销毁
+            let visibility = if raw_camel_case_name == "startDestroy" { "pub(crate)" } else { "pub" };
+            lines.push(format!("{}{visibility} fn {snake_fn_name}(&mut self", *INDENT));
             if !child_lines_rs.is_empty() {
                 lines.push(format!(", "));
             }
             lines.extend(child_lines_rs);
             let c_result_type = entity.get_result_type().unwrap().get_display_name();
-            let rust_result_type = get_rs_result_type_from_c_result_type(&c_result_type);
+            // YDQueryResult 由 QueryResult 在 drop 时调用 destroy，它借用 api，因为其中的元素可能指向 api 持有的数据；
+            // const char * 返回值在 api 销毁前有效，借用 api 并按 GB18030 解码，ASCII 不会拷贝
+            let (rust_result_type, call_prefix, call_suffix) = match get_query_result_element_type(&c_result_type) {
+                Some(element_type) => (
+                    format!("Option<QueryResult<'_, {element_type}>>"),
+                    "QueryResult::from_raw(",
+                    ")",
+                ),
+                None if c_result_type == "const char *" => (
+                    "Option<std::borrow::Cow<'_, str>>".to_string(),
+                    "crate::gb18030_ptr_to_str(",
+                    ")",
+                ),
+                None => (get_rs_result_type_from_c_result_type(&c_result_type), "", ""),
+            };
+            // `const char *` 参数含有 \0 时返回 NulError，而不是 panic
+            let c_string_names = get_c_string_parameter_names(entity);
+            let (rust_result_type, call_prefix, call_suffix) = if c_string_names.is_empty() {
+                (rust_result_type, call_prefix.to_string(), call_suffix.to_string())
+            } else {
+                (
+                    format!("Result<{rust_result_type}, std::ffi::NulError>"),
+                    format!("Ok({call_prefix}"),
+                    format!("{call_suffix})"),
+                )
+            };
+            let c_string_conversions = c_string_names
+                .iter()
+                .map(|name| format!("{indent}{indent}let {name} = std::ffi::CString::new({name})?;\n", indent = *INDENT))
+                .collect::<String>();
             let full_api_record_name = format!("{record_name}_{camel_case_name}");
             let child_lines_c_method_call_param = process_children(
                 entity,
@@ -131,10 +227,16 @@ pub fn handle_function_prototype(
                     ..configs.clone()
                 },
             );
+            // void 方法不写返回类型，`-> ()` 会被 clippy 报告
+            let return_type = if rust_result_type == "()" {
+                String::new()
+            } else {
+                format!(" -> {rust_result_type}")
+            };
             lines.push(format!(
-                r#") -> {rust_result_type} {{
-        unsafe {{
-            ((*(*self).vtable_).{full_api_record_name})(self as *mut {record_name}"#
+                r#"){return_type} {{
+{c_string_conversions}        unsafe {{
+            {call_prefix}(self.vtable().{full_api_record_name})(self as *mut {record_name}"#
             ));
             // console_debug!("{full_api_record_name} {:?}", child_lines_c);
             if !child_lines_c_method_call_param.is_empty() {
@@ -142,12 +244,40 @@ pub fn handle_function_prototype(
             }
             lines.extend(child_lines_c_method_call_param);
             lines.push(format!(
-                r#")
+                r#"){call_suffix}
         }}
     }}
 "#
             ));
         }
+        MethodFlavor::ApiVTableStruct => {
+            let child_lines_ffi_param = process_children(
+                entity,
+                handlers,
+                &mut HandlerConfigs {
+                    parameter_flavor: ParameterFlavor::Rust,
+                    prefer_pointer: true,
+                    ..configs.clone()
+                },
+            );
+            let c_result_type = entity.get_result_type().unwrap().get_display_name();
+            let rust_result_type = get_rs_result_type_from_c_result_type(&c_result_type);
+            let full_api_record_name = format!("{record_name}_{camel_case_name}");
+            lines.push(format!(
+                // catalog.rs 直接使用 v-table 中的函数
+                r#"{}pub(crate) {full_api_record_name}: unsafe extern "C" fn(this: *mut {record_name}"#,
+                *INDENT
+            ));
+            if !child_lines_ffi_param.is_empty() {
+                lines.push(format!(", "));
+            }
+            lines.extend(child_lines_ffi_param);
+            if entity.get_type().map_or(false, |t| t.is_variadic()) {
+                // writeLog(const char *format,...)
+                lines.push(format!(", ..."));
+            }
+            lines.push(format!(") -> {rust_result_type},\n"));
+        }
         MethodFlavor::VTableStruct => {
             lines.push(format!(
                 r#"{}{snake_fn_name}: extern "C" fn(spi: *mut {record_name}Fat"#,
@@ -175,47 +305,28 @@ pub fn handle_function_prototype(
             ));
         }
         MethodFlavor::OutputEnum => {
-            let config_for_children = &mut HandlerConfigs {
-                // ask function handler to output trait style code
-                parameter_flavor: ParameterFlavor::RustStruct,
-                ..configs.clone()
-            };
-            process_children(entity, handlers, config_for_children);
-            let life_time_param_on_parent = if config_for_children.life_time_on_children {
-                "<'a>"
-            } else {
-                ""
-            };
-            configs.life_time_on_children = false;
-            lines.push(format!(
-                "{}{enum_name}({packet_name_prefix}Packet{life_time_param_on_parent}),\n",
-                *INDENT
-            ));
+            lines.push(format!("{}{enum_name}({packet_name_prefix}Packet),\n", *INDENT));
         }
         MethodFlavor::OutputEnumStruct => {
             let config_for_children = &mut HandlerConfigs {
                 // ask function handler to output trait style code
                 parameter_flavor: ParameterFlavor::RustStruct,
-                life_time: "'a ".to_string(),
                 ..configs.clone()
             };
             let child_lines_rs_struct = process_children(entity, handlers, config_for_children);
-            // life_time_param_on_parent = config_for_children.life_time_on_children ? "<'a>" : "";
-            let life_time_param_on_parent = if config_for_children.life_time_on_children {
-                "<'a>"
-            } else {
-                ""
-            };
-            configs.life_time_on_children = false;
             lines.push(format!(
                 r#"
-#[allow(unused_lifetimes)]
+/// copied at callback time, so it can be consumed after libyd reuses or frees the original data
 #[derive(Clone)]
-pub struct {packet_name_prefix}Packet{life_time_param_on_parent} {{
+pub struct {packet_name_prefix}Packet {{
 "#
             ));
             lines.extend(child_lines_rs_struct);
             lines.push(format!("\n}}\n"));
+            if config_for_children.snapshot_on_children {
+                // 拷贝出来的结构体可能含有指向 YDInstrument 等的指针，读取它们的 accessor 都是 unsafe 的，只能在 unsafe 代码里解引用
+                lines.push(format!("\nunsafe impl Send for {packet_name_prefix}Packet {{}}\n"));
+            }
         }
         MethodFlavor::CFn => {
             let child_lines_rs_c_fn = process_children(
@@ -268,6 +379,48 @@ extern "C" fn spi_{snake_fn_name}(spi: *mut {record_name}Fat"#
                 },
             );
             lines.extend(child_lines_c);
+            if raw_camel_case_name == "notifyBeforeApiDestroy" {
+                lines.push(format!(
+                    r#");
+        if let Some(on_destroy_phase) = &(*spi).on_destroy_phase {{
+            on_destroy_phase(ApiDestroyPhase::BeforeApiDestroy);
+        }}
+    }}
+}}
+"#
+                ));
+                return lines;
+            }
+            if raw_camel_case_name == "notifyEvent" {
+                // YD_AE_ServerRestarted 时 libyd 在回调返回后退出进程，只有在回调内调用 startDestroy 才能避免，见 ydApi.h
+                lines.push(format!(
+                    r#");
+        if api_event == YD_AE_ServerRestarted {{
+            if let Some(on_server_restarted) = &(*spi).on_server_restarted {{
+                on_server_restarted();
+            }}
+        }}
+    }}
+}}
+"#
+                ));
+                return lines;
+            }
+            if raw_camel_case_name == "notifyAfterApiDestroy" {
+                // libyd 不会再调用这个 listener，见 ydApi.h 中 startDestroy 的说明，释放之后再通知 YDApiHandle
+                lines.push(format!(
+                    r#");
+        let on_destroy_phase = (*spi).on_destroy_phase.take();
+        {record_name}Fat::release(spi);
+        if let Some(on_destroy_phase) = on_destroy_phase {{
+            on_destroy_phase(ApiDestroyPhase::AfterApiDestroy);
+        }}
+    }}
+}}
+"#
+                ));
+                return lines;
+            }
             lines.push(format!(
                 r#")
     }}
@@ -290,7 +443,7 @@ extern "C" fn spi_{snake_fn_name}(spi: *mut {record_name}Fat"#
             lines.extend(child_lines_rs_param.clone());
             lines.push(format!(
                 r#") {{
-        self.inner.lock().unwrap().push("#
+        self.sink.push("#
             ));
             lines.push(format!(
                 "{full_spi_output_enum_name}::{enum_name}({packet_name_prefix}Packet {{\n",
@@ -320,6 +473,178 @@ extern "C" fn spi_{snake_fn_name}(spi: *mut {record_name}Fat"#
     lines
 }
 
+/// `bool insertMultiOrders(unsigned count,YDInputOrder inputOrders[],const YDInstrument *instruments[],const YDAccount *pAccount=NULL)`
+/// and its cancel/quote siblings. The maximum count is taken from the doc comment in ydApi.h, e.g. "maximum value of count is 16".
+/// When the slices are invalid, C++ is not called, and `ErrorNo` of every element is set like libyd does.
+fn handle_multi_method(
+    entity: &Entity,
+    record_name: &str,
+    snake_fn_name: &str,
+    full_api_record_name: &str,
+) -> String {
+    let arguments = entity.get_arguments().unwrap();
+    let [_, elements, targets, account] = arguments.as_slice() else {
+        panic!("Unexpected parameters of {snake_fn_name}: {arguments:?}");
+    };
+    let elements_name = Inflector::to_snake_case(&elements.get_name().unwrap());
+    let targets_name = Inflector::to_snake_case(&targets.get_name().unwrap());
+    let account_name = Inflector::to_snake_case(&account.get_name().unwrap());
+    let element_type_name = get_full_name_of_entity(
+        &elements.get_type().unwrap().get_element_type().unwrap().get_declaration().unwrap(),
+    );
+    let target_type_name = get_full_name_of_entity(
+        &targets
+            .get_type()
+            .unwrap()
+            .get_element_type()
+            .unwrap()
+            .get_pointee_type()
+            .unwrap()
+            .get_declaration()
+            .unwrap(),
+    );
+    let account_type_name = get_full_name_of_entity(
+        &account.get_type().unwrap().get_pointee_type().unwrap().get_declaration().unwrap(),
+    );
+    // ydError.h 没有报价数量超限的错误码，报价用字段错误，由 `MultiRequestError::TooManyInMultiQuotes` 区分
+    let (field_error, too_many_error, too_many_variant) = match element_type_name.as_str() {
+        "YDInputOrder" => ("YD_ERROR_OrderFieldError", "YD_ERROR_TooManyInMultiOrders", "TooManyInMultiOrders"),
+        "YDCancelOrder" => ("YD_ERROR_CancelOrderFieldError", "YD_ERROR_TooManyInMultiOrders", "TooManyInMultiOrders"),
+        "YDInputQuote" => ("YD_ERROR_QuoteFieldError", "YD_ERROR_QuoteFieldError", "TooManyInMultiQuotes"),
+        "YDCancelQuote" => ("YD_ERROR_CancelQuoteFieldError", "YD_ERROR_CancelQuoteFieldError", "TooManyInMultiQuotes"),
+        _ => panic!("Unexpected element type of {snake_fn_name}: {element_type_name}"),
+    };
+    let comment = entity.get_comment().unwrap_or_default();
+    // 旧版本 SDK 的注释里可能没有最大数量，这时只检查两个切片长度一致，数量交给 libyd 检查
+    let max_count: Option<usize> = comment
+        .split("maximum value of count is")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|count| count.parse().ok());
+    if max_count.is_none() {
+        println!("cargo:warning=Can't find maximum count of {snake_fn_name} in ydApi.h, only the length of slices is checked");
+    }
+    let (max_count_doc, max_count) = match max_count {
+        Some(max_count) => (format!(", which should not exceed {max_count}"), max_count.to_string()),
+        None => (String::new(), "std::os::raw::c_uint::MAX as usize".to_string()),
+    };
+    let doc_lines = comment
+        .lines()
+        .map(|line| format!("{}/// {}\n", *INDENT, line.trim().trim_start_matches('/').trim()))
+        .collect::<String>();
+    format!(
+        r#"
+{doc_lines}    ///
+    /// `{targets_name}[i]` is used for `{elements_name}[i]`, so both slices must have the same length{max_count_doc}.
+    /// Otherwise libyd is not called, and `ErrorNo` of every element is set to `{too_many_error}` or `{field_error}`.
+    /// `{account_name}` is only needed by monitor accounts, `None` is the logged in account.
+    pub fn {snake_fn_name}(&mut self, {elements_name}: &mut [{element_type_name}], {targets_name}: &[&{target_type_name}], {account_name}: Option<&{account_type_name}>) -> Result<bool, crate::MultiRequestError> {{
+        const MAX_COUNT: usize = {max_count};
+        let (count, target_count) = ({elements_name}.len(), {targets_name}.len());
+        let error = if count > MAX_COUNT {{
+            Some((crate::MultiRequestError::{too_many_variant} {{ count, max_count: MAX_COUNT }}, {too_many_error}))
+        }} else if count != target_count {{
+            Some((crate::MultiRequestError::LengthMismatch {{ count, target_count }}, {field_error}))
+        }} else {{
+            None
+        }};
+        if let Some((error, error_no)) = error {{
+            for element in {elements_name}.iter_mut() {{
+                element.ErrorNo = error_no;
+            }}
+            return Err(error);
+        }}
+        Ok(unsafe {{
+            (self.vtable().{full_api_record_name})(
+                self as *mut {record_name},
+                count as std::os::raw::c_uint,
+                {elements_name}.as_mut_ptr(),
+                // &{target_type_name} has the same layout as *const {target_type_name}, libyd only reads this array
+                {targets_name}.as_ptr() as *mut *const {target_type_name},
+                // pAccount=NULL in ydApi.h
+                {account_name}.map_or(std::ptr::null(), |account| account as *const {account_type_name}),
+            )
+        }})
+    }}
+"#
+    )
+}
+
+/// `bool cancelOrder(YDCancelOrder *pCancelOrder,const YDExchange *pExchange,const YDAccount *pAccount=NULL)`
+/// and `cancelQuote`, the account is optional like `handle_multi_method`
+fn handle_cancel_method(
+    entity: &Entity,
+    record_name: &str,
+    snake_fn_name: &str,
+    full_api_record_name: &str,
+) -> String {
+    let arguments = entity.get_arguments().unwrap();
+    let [cancel, exchange, account] = arguments.as_slice() else {
+        panic!("Unexpected parameters of {snake_fn_name}: {arguments:?}");
+    };
+    let type_name = |argument: &Entity| {
+        get_full_name_of_entity(&argument.get_type().unwrap().get_pointee_type().unwrap().get_declaration().unwrap())
+    };
+    let cancel_name = Inflector::to_snake_case(&cancel.get_name().unwrap());
+    let exchange_name = Inflector::to_snake_case(&exchange.get_name().unwrap());
+    let account_name = Inflector::to_snake_case(&account.get_name().unwrap());
+    let (cancel_type_name, exchange_type_name, account_type_name) =
+        (type_name(cancel), type_name(exchange), type_name(account));
+    format!(
+        r#"
+    /// `{cancel_type_name}::new` sets the order or quote to cancel by `CancelTarget`.
+    /// `{account_name}` is only needed by monitor accounts, `None` is the logged in account.
+    pub fn {snake_fn_name}(&mut self, {cancel_name}: &mut {cancel_type_name}, {exchange_name}: &{exchange_type_name}, {account_name}: Option<&{account_type_name}>) -> bool {{
+        unsafe {{
+            (self.vtable().{full_api_record_name})(
+                self as *mut {record_name},
+                {cancel_name},
+                {exchange_name},
+                // pAccount=NULL in ydApi.h
+                {account_name}.map_or(std::ptr::null(), |account| account as *const {account_type_name}),
+            )
+        }}
+    }}
+"#
+    )
+}
+
+/// C++ 虚析构函数在 Itanium ABI 下占用两个 v-table 位置（complete object destructor 和 deleting destructor），在 MSVC 下只占一个。
+/// listener 由 rust 这边持有，libyd 不会 delete 它，所以 SPI 的析构函数什么也不做；API 的析构函数也不应该被调用。
+fn handle_destructor(configs: &HandlerConfigs) -> Vec<String> {
+    let record_name = &configs.record_name;
+    let snake_record_name = Inflector::to_snake_case(record_name);
+    match configs.method_flavor {
+        MethodFlavor::VTableStruct => vec![format!(
+            r#"{indent}destructor: extern "C" fn(spi: *mut {record_name}Fat),
+{indent}#[cfg(not(target_os = "windows"))]
+{indent}deleting_destructor: extern "C" fn(spi: *mut {record_name}Fat),
+"#,
+            indent = *INDENT
+        )],
+        MethodFlavor::StaticTable => vec![format!(
+            r#"{indent}destructor: spi_{snake_record_name}_destructor,
+{indent}#[cfg(not(target_os = "windows"))]
+{indent}deleting_destructor: spi_{snake_record_name}_destructor,
+"#,
+            indent = *INDENT
+        )],
+        MethodFlavor::CFn => vec![format!(
+            r#"
+extern "C" fn spi_{snake_record_name}_destructor(spi: *mut {record_name}Fat) {{}}
+"#
+        )],
+        MethodFlavor::ApiVTableStruct => vec![format!(
+            r#"{indent}{record_name}_destructor: unsafe extern "C" fn(this: *mut {record_name}),
+{indent}#[cfg(not(target_os = "windows"))]
+{indent}{record_name}_deleting_destructor: unsafe extern "C" fn(this: *mut {record_name}),
+"#,
+            indent = *INDENT
+        )],
+        _ => vec![],
+    }
+}
+
 pub fn find_previous_sibling_index(entity: &Entity, configs: &HandlerConfigs) -> usize {
     let current_name = entity.get_name().unwrap();
     let mut index = 0;
@@ -327,8 +652,9 @@ pub fn find_previous_sibling_index(entity: &Entity, configs: &HandlerConfigs) ->
     if let Some(parent) = entity.get_lexical_parent() {
         let siblings = parent.get_children();
 
-        // Ensure we only iterate up to the current entity's index as specified in configs
-        for sibling in siblings.iter().take(configs.index + 1) {
+        // Only count overloads declared before the current entity. `configs.index` can't be used here,
+        // it only counts children with handler, while siblings also contain access and base specifiers
+        for sibling in siblings.iter().take_while(|sibling| *sibling != entity) {
             if sibling.get_kind() == entity.get_kind() {
                 if sibling.get_name().unwrap() == current_name {
                     // Increment index for each sibling with the same name found
@@ -340,14 +666,44 @@ pub fn find_previous_sibling_index(entity: &Entity, configs: &HandlerConfigs) ->
     index
 }
 
+/// snake case names of `const char *` parameters, which are `&str` in API methods
+fn get_c_string_parameter_names(entity: &Entity) -> Vec<String> {
+    entity
+        .get_arguments()
+        .unwrap_or_default()
+        .iter()
+        .filter(|argument| {
+            argument
+                .get_type()
+                .and_then(|argument_type| argument_type.get_pointee_type())
+                .map_or(false, |pointee_type| pointee_type.get_kind() == TypeKind::CharS)
+        })
+        .map(|argument| Inflector::to_snake_case(&argument.get_name().unwrap()))
+        .collect()
+}
+
+/// `YDQueryResult<char> *` -> `c_char`, `YDQueryResult<YDExtendedOrder> *` -> `YDExtendedOrder`
+fn get_query_result_element_type(c_result_type: &str) -> Option<String> {
+    let element_type = c_result_type.strip_prefix("YDQueryResult<")?.strip_suffix("> *")?;
+    Some(match element_type {
+        "char" => "std::os::raw::c_char".to_string(),
+        _ => element_type.to_string(),
+    })
+}
+
 fn get_rs_result_type_from_c_result_type(c_result_type: &str) -> String {
     match c_result_type {
         "void" => "()".to_string(),
         "int" => "std::os::raw::c_int".to_string(),
         "bool" => "bool".to_string(),
+        "unsigned int" => "std::os::raw::c_uint".to_string(),
+        "double" => "f64".to_string(),
         "const char *" => "*const std::os::raw::c_char".to_string(),
-        "YDQueryResult<char> *" => "*const YDQueryResult".to_string(),
         _ => {
+            if let Some(element_type) = get_query_result_element_type(c_result_type) {
+                // v-table 中是裸指针，ApiTrait 中包装成 QueryResult
+                return format!("*mut RawQueryResult<{element_type}>");
+            }
             if c_result_type.starts_with("const ") && c_result_type.ends_with(" *") {
                 let t = &c_result_type[6..c_result_type.len() - 2];
                 format!("*const {}", t)
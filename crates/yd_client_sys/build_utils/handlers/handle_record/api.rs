@@ -1,7 +1,8 @@
 use clang::*;
 
 use crate::build_utils::{
-    config::HandlerConfigs, handle_function_prototype::MethodFlavor, process_children, HandlerMap,
+    config::HandlerConfigs, format_name::get_full_name_of_entity, get_base_records,
+    handle_function_prototype::MethodFlavor, process_children, HandlerMap,
 };
 
 pub fn handle_api_record(
@@ -13,6 +14,19 @@ pub fn handle_api_record(
     let mut lines: Vec<String> = Vec::new();
     let vtable_struct_name = format!("{full_rust_struct_name}VTable");
     let full_trait_name = format!("{full_rust_struct_name}Trait");
+    // YDExtendedApi: public YDApi
+    let base_record_name = get_base_records(entity)
+        .first()
+        .map(|base| get_full_name_of_entity(base));
+
+    lines.push(format!("\n/* Generated by handle_api_vtable */"));
+    lines.extend(handle_api_vtable(
+        entity,
+        handlers,
+        configs,
+        &vtable_struct_name,
+        &base_record_name,
+    ));
 
     lines.push(format!("\n/* Generated by handle_api */"));
     lines.extend(handle_api(
@@ -20,8 +34,44 @@ pub fn handle_api_record(
         handlers,
         configs,
         &full_rust_struct_name,
+        &vtable_struct_name,
     ));
 
+    if let Some(base_record_name) = base_record_name {
+        lines.push(format!("\n/* Generated by handle_api_deref */"));
+        lines.push(handle_api_deref(full_rust_struct_name, &base_record_name));
+    }
+
+    lines
+}
+
+/// bindgen 不会为带虚析构函数或者有基类的 class 生成 v-table，所以我们自己按声明顺序生成，基类的 v-table 在最前面
+pub fn handle_api_vtable(
+    entity: &Entity,
+    handlers: &HandlerMap,
+    configs: &HandlerConfigs,
+    vtable_struct_name: &str,
+    base_record_name: &Option<String>,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    lines.push(format!(
+        r#"
+#[repr(C)]
+pub struct {vtable_struct_name} {{
+"#
+    ));
+    if let Some(base_record_name) = base_record_name {
+        lines.push(format!("    _base: {base_record_name}VTable,\n"));
+    }
+    lines.extend(process_children(
+        entity,
+        handlers,
+        &mut HandlerConfigs {
+            method_flavor: MethodFlavor::ApiVTableStruct,
+            ..configs.clone()
+        },
+    ));
+    lines.push("}\n".to_string());
     lines
 }
 
@@ -30,10 +80,15 @@ pub fn handle_api(
     handlers: &HandlerMap,
     configs: &HandlerConfigs,
     full_rust_struct_name: &str,
+    vtable_struct_name: &str,
 ) -> Vec<String> {
     let mut lines = Vec::new();
     lines.push(format!(r#"
 impl {full_rust_struct_name} {{
+    fn vtable(&self) -> &'static {vtable_struct_name} {{
+        // v-table pointer is the first field of C++ object
+        unsafe {{ &**(self as *const {full_rust_struct_name} as *const *const {vtable_struct_name}) }}
+    }}
 "#));
     lines.extend(process_children(
         entity,
@@ -45,21 +100,30 @@ impl {full_rust_struct_name} {{
     ));
     lines.push(format!(
         r#"
-    /// encapsulate the raw pointer within YDApi
-    pub unsafe fn from_raw(api_ptr: *mut YDApi) -> Box<YDApi> {{
-        // Ensure the pointer is not null
-        if api_ptr.is_null() {{
-            panic!("YDApi pointer is null");
-        }}
-
-        // Dereference the pointer to obtain YDApi and return it
-        // This assumes that YDApi can be directly obtained from the pointer
-        // Adjust based on your actual struct layout
-        Box::from_raw(api_ptr)
-    }}
 }}
 unsafe impl Send for {full_rust_struct_name} {{}}
 "#
     ));
     lines
 }
+
+/// 让子类可以直接调用基类的方法，例如 `YDExtendedApi` 调用 `YDApi::login`
+pub fn handle_api_deref(full_rust_struct_name: &str, base_record_name: &str) -> String {
+    format!(
+        r#"
+impl std::ops::Deref for {full_rust_struct_name} {{
+    type Target = {base_record_name};
+
+    fn deref(&self) -> &{base_record_name} {{
+        &self._base
+    }}
+}}
+
+impl std::ops::DerefMut for {full_rust_struct_name} {{
+    fn deref_mut(&mut self) -> &mut {base_record_name} {{
+        &mut self._base
+    }}
+}}
+"#
+    )
+}
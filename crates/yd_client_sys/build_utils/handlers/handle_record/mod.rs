@@ -18,6 +18,11 @@ pub enum RecordFlavor {
     None,
 }
 
+/// callback classes we generate trait, stream and v-table for
+pub const SPI_RECORD_NAMES: [&str; 2] = ["YDListener", "YDExtendedListener"];
+/// classes implemented by libyd that we generate safe wrapper for
+pub const API_RECORD_NAMES: [&str; 2] = ["YDApi", "YDExtendedApi"];
+
 pub fn handle_record(
     entity: &Entity,
     handlers: &HandlerMap,
@@ -25,11 +30,15 @@ pub fn handle_record(
 ) -> Vec<String> {
     let record_name = entity.get_display_name().unwrap_or_default();
     let mut lines: Vec<String> = vec![];
+    if !entity.is_definition() {
+        // forward declaration like `class YDInstrument;`
+        return lines;
+    }
     configs.record_name = record_name;
     let full_rust_struct_name = get_full_name_of_entity(&entity);
     match configs.record_flavor {
         RecordFlavor::SPI => {
-            if full_rust_struct_name == "YDListener" {
+            if SPI_RECORD_NAMES.contains(&full_rust_struct_name.as_str()) {
                 lines.extend(handle_spi_record(
                     entity,
                     handlers,
@@ -39,7 +48,7 @@ pub fn handle_record(
             }
         }
         RecordFlavor::API => {
-            if full_rust_struct_name == "YDApi" {
+            if API_RECORD_NAMES.contains(&full_rust_struct_name.as_str()) {
                 lines.extend(handle_api_record(
                     entity,
                     handlers,
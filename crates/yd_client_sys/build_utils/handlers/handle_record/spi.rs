@@ -2,7 +2,8 @@ use clang::*;
 use inflector::Inflector;
 
 use crate::build_utils::{
-    config::HandlerConfigs, handle_function_prototype::MethodFlavor, process_children, HandlerMap,
+    config::HandlerConfigs, format_name::format_enum_name, handle_function_prototype::MethodFlavor, process_children,
+    HandlerMap,
 };
 
 use super::RecordFlavor;
@@ -73,6 +74,8 @@ pub fn handle_spi_record(
     lines.push(handle_spi_stream_code(
         full_rust_struct_name,
         &format!("{full_rust_struct_name}Output"),
+        &get_create_spi_fn_name(full_rust_struct_name),
+        find_market_data_enum_name(entity).as_deref(),
     ));
 
     lines.push(format!("\n/* Generated by handle_spi_fn */"));
@@ -142,9 +145,11 @@ pub fn handle_spi_output_enum(
     full_rust_struct_name: &str,
 ) -> Vec<String> {
     let mut lines = Vec::new();
-    let full_spi_output_enum_name = format!("{full_rust_struct_name}Output<'a>");
+    let full_spi_output_enum_name = format!("{full_rust_struct_name}Output");
     lines.push(format!(
         r#"
+/// packets are copied by value, boxing them would cost an allocation per callback
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone)]
 pub enum {full_spi_output_enum_name} {{
 "#
@@ -227,60 +232,125 @@ pub fn handle_spi_fat(
     vtable_struct_name: &str,
     full_trait_name: &str,
 ) -> String {
+    let full_static_vtable_var_name =
+        Inflector::to_snake_case(full_rust_struct_name).to_uppercase() + "_VTABLE";
     format!(
         r#"
+/// the C++ object passed to libyd, it owns the listener until libyd will not call it anymore
 #[repr(C)]
 pub struct {full_rust_struct_name}Fat<'a> {{
   vtable: *const {vtable_struct_name},
-  pub md_spi_ptr: *mut dyn {full_trait_name}<'a>,
+  pub md_spi_ptr: *mut (dyn {full_trait_name}<'a> + 'a),
+  /// run after the listener is dropped, e.g. release the YDExtendedListener registered together
+  pub on_release: Option<Box<dyn FnOnce() + 'a>>,
+  /// run after `notifyBeforeApiDestroy` and `notifyAfterApiDestroy` are passed to the listener
+  pub on_destroy_phase: Option<Box<dyn Fn(ApiDestroyPhase) + Send + 'a>>,
+  /// run inside `notifyEvent(YD_AE_ServerRestarted)` after the listener, libyd exits the process once the callback
+  /// returns, unless `startDestroy` is called here
+  pub on_server_restarted: Option<Box<dyn Fn() + Send + 'a>>,
+}}
+
+impl<'a> {full_rust_struct_name}Fat<'a> {{
+    pub fn into_raw(listener: Box<dyn {full_trait_name}<'a> + 'a>) -> *mut Self {{
+        Box::into_raw(Box::new(Self {{
+            vtable: &{full_static_vtable_var_name},
+            md_spi_ptr: Box::into_raw(listener),
+            on_release: None,
+            on_destroy_phase: None,
+            on_server_restarted: None,
+        }}))
+    }}
+
+    /// # Safety
+    ///
+    /// `fat` must come from `into_raw`, and libyd must not call it anymore,
+    /// that is, `start` failed or `notifyAfterApiDestroy` is called
+    pub unsafe fn release(fat: *mut Self) {{
+        let fat = Box::from_raw(fat);
+        drop(Box::from_raw(fat.md_spi_ptr));
+        if let Some(on_release) = fat.on_release {{
+            on_release();
+        }}
+    }}
 }}
 "#
     )
 }
 
-pub fn handle_spi_stream_code(full_spi_name: &str, full_spi_output_enum_name: &str) -> String {
+/// market data can be dropped or coalesced by `OverflowPolicy`, other events are never dropped
+fn find_market_data_enum_name(entity: &Entity) -> Option<String> {
+    entity
+        .get_children()
+        .iter()
+        .filter_map(|child| child.get_name())
+        .find(|name| name == "notifyMarketData")
+        .map(|name| format_enum_name(&name))
+}
+
+/// `YDListener` -> `create_spi`, `YDExtendedListener` -> `create_extended_spi`
+pub fn get_create_spi_fn_name(full_spi_name: &str) -> String {
+    let middle_name = full_spi_name
+        .trim_start_matches("YD")
+        .trim_end_matches("Listener");
+    if middle_name.is_empty() {
+        "create_spi".to_string()
+    } else {
+        format!("create_{}_spi", Inflector::to_snake_case(middle_name))
+    }
+}
+
+pub fn handle_spi_stream_code(
+    full_spi_name: &str,
+    full_spi_output_enum_name: &str,
+    create_spi_fn_name: &str,
+    market_data_enum_name: Option<&str>,
+) -> String {
+    let market_data_instrument_ref = match market_data_enum_name {
+        Some(enum_name) => format!(
+            r#"match self {{
+            Self::{enum_name}(packet) => Some(packet.market_data.InstrumentRef),
+            _ => None,
+        }}"#
+        ),
+        None => "None".to_string(),
+    };
     format!(
         r#"
-    use futures::stream::Stream;
-    use std::{{
-        pin::Pin,
-        sync::{{Arc, Mutex}},
-        task::Waker,
-    }};
+impl SpiQueueItem for {full_spi_output_enum_name} {{
+    fn market_data_instrument_ref(&self) -> Option<i32> {{
+        {market_data_instrument_ref}
+    }}
+}}
 
-struct {full_spi_name}Inner<'a> {{
-    buf: std::collections::VecDeque<{full_spi_output_enum_name}<'a>>,
-    waker: Option<Waker>,
+pub struct {full_spi_name}Stream {{
+    queue: Arc<SpiQueue<{full_spi_output_enum_name}>>,
 }}
 
-impl<'a> {full_spi_name}Inner<'a> {{
-    fn push(&mut self, msg: {full_spi_output_enum_name}<'a>) {{
-        self.buf.push_back(msg);
-        if let Some(ref waker) = &self.waker {{
-            waker.clone().wake()
-        }}
-    }}
+/// the listener registered to libyd, shares the queue with `{full_spi_name}Stream`,
+/// so either side can be dropped first. Packets are owned copies, so the stream is `Send + 'static`
+pub struct {full_spi_name}Spi<S = SpiQueueSender<{full_spi_output_enum_name}>> {{
+    sink: S,
 }}
 
-pub struct {full_spi_name}Stream<'a> {{
-    inner: Arc<Mutex<{full_spi_name}Inner<'a>>>,
+/// pushes to a lock-free ring instead of the async stream
+pub type {full_spi_name}RingSpi = {full_spi_name}Spi<RingProducer<{full_spi_output_enum_name}>>;
+
+pub type {full_spi_name}RingConsumer = RingConsumer<{full_spi_output_enum_name}>;
+
+impl {full_spi_name}Stream {{
+    pub fn metrics(&self) -> SpiQueueMetrics {{
+        self.queue.metrics()
+    }}
 }}
 
-impl<'a> Stream for {full_spi_name}Stream<'a> {{
-    type Item = {full_spi_output_enum_name}<'a>;
+impl Stream for {full_spi_name}Stream {{
+    type Item = {full_spi_output_enum_name};
 
     fn poll_next(
         self: Pin<&mut Self>,
         cx: &mut futures::task::Context<'_>,
     ) -> futures::task::Poll<Option<Self::Item>> {{
-        use futures::task::Poll;
-        let mut inner = self.inner.lock().unwrap();
-        if let Some(i) = inner.buf.pop_front() {{
-            Poll::Ready(Some(i))
-        }} else {{
-            inner.waker = Some(cx.waker().clone());
-            Poll::Pending
-        }}
+        self.queue.poll_pop(cx)
     }}
 
     fn size_hint(&self) -> (usize, Option<usize>) {{
@@ -288,18 +358,36 @@ impl<'a> Stream for {full_spi_name}Stream<'a> {{
     }}
 }}
 
-pub fn create_spi() -> (Box<{full_spi_name}Stream<'static>>, *mut {full_spi_name}Stream<'static>) {{
-    let i = {full_spi_name}Inner {{
-        buf: std::collections::VecDeque::new(),
-        waker: None,
+impl Drop for {full_spi_name}Stream {{
+    fn drop(&mut self) {{
+        // 不再有人消费事件，避免 libyd 的回调线程阻塞或者队列无限增长
+        self.queue.close();
+    }}
+}}
+
+/// unbounded queue, same as `{create_spi_fn_name}_with_queue(SpiQueueConfig::default())`
+pub fn {create_spi_fn_name}() -> (Box<{full_spi_name}Stream>, Box<{full_spi_name}Spi>) {{
+    {create_spi_fn_name}_with_queue(SpiQueueConfig::default())
+}}
+
+pub fn {create_spi_fn_name}_with_queue(
+    config: SpiQueueConfig,
+) -> (Box<{full_spi_name}Stream>, Box<{full_spi_name}Spi>) {{
+    let queue = Arc::new(SpiQueue::new(config));
+    let stream = {full_spi_name}Stream {{
+        queue: queue.clone(),
     }};
-    let xspi = {full_spi_name}Stream {{
-        inner: Arc::new(Mutex::new(i)),
+    let spi = {full_spi_name}Spi {{
+        sink: SpiQueueSender::new(queue),
     }};
-    let myspi = Box::new(xspi);
-    let pp = Box::into_raw(myspi);
-    let pp2 = pp.clone();
-    (unsafe {{ Box::from_raw(pp2) }}, pp)
+    (Box::new(stream), Box::new(spi))
+}}
+
+/// for consumers busy-polling from a pinned thread, the callback doesn't lock or allocate until the ring is full,
+/// then market data is handled by `config.overflow_policy` and trading events are spilled
+pub fn {create_spi_fn_name}_with_ring(config: SpiRingConfig) -> ({full_spi_name}RingConsumer, Box<{full_spi_name}RingSpi>) {{
+    let (producer, consumer) = spsc_ring(config);
+    (consumer, Box::new({full_spi_name}Spi {{ sink: producer }}))
 }}
 "#,
     )
@@ -314,7 +402,7 @@ pub fn handle_spi_fn(
 ) -> Vec<String> {
     let mut lines = Vec::new();
     lines.push(format!(
-        "\nimpl<'a> {full_trait_name}<'a> for {full_rust_struct_name}Stream<'a> {{\n",
+        "\nimpl<'a, S: SpiSink<{full_rust_struct_name}Output>> {full_trait_name}<'a> for {full_rust_struct_name}Spi<S> {{\n",
     ));
     lines.extend(process_children(
         entity,
@@ -2,8 +2,14 @@
 use clang::*;
 use std::collections::HashMap;
 
+pub mod accessors;
+pub mod data_struct;
+pub mod data_types;
 mod format_name;
 pub mod handlers;
+pub mod sdk;
+pub mod string_fields;
+pub mod yd_error;
 pub use self::config::HandlerConfigs;
 use handlers::*;
 
@@ -34,6 +40,10 @@ pub fn create_handlers() -> HandlerMap {
 }
 
 fn get_handler<'a>(entity: &'a Entity<'a>, handlers: &'a HandlerMap) -> Option<&'a Handler> {
+    // base classes are walked explicitly by the record handlers, see `get_base_records`
+    if entity.get_kind() == EntityKind::BaseSpecifier {
+        return None;
+    }
     entity
         .get_type()
         .and_then(|node_type| handlers.get(&node_type.get_kind()))
@@ -85,6 +95,17 @@ fn get_child_index_with_same_handler(
     count
 }
 
+/// get the definitions of the classes that `entity` directly inherits from, e.g. `YDApi` for `YDExtendedApi`
+pub fn get_base_records<'tu>(entity: &Entity<'tu>) -> Vec<Entity<'tu>> {
+    entity
+        .get_children()
+        .into_iter()
+        .filter(|c| c.get_kind() == EntityKind::BaseSpecifier)
+        .filter_map(|c| c.get_type())
+        .filter_map(|t| t.get_declaration())
+        .filter_map(|d| d.get_definition())
+        .collect()
+}
 
 pub fn process_children(
     entity: &Entity,
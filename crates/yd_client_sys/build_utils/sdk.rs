@@ -0,0 +1,62 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+/// SDKs checked in thirdparty/, selected by cargo features like `sdk-1-386-40-0`, the last one is the default
+/// (cargo feature env name, version, directory relative to thirdparty/)
+const BUNDLED_SDKS: [(&str, &str, &str); 1] = [("CARGO_FEATURE_SDK_1_386_40_0", "1.386.40.0", "ydClient/ydAPI_c++")];
+
+/// the `ydAPI_c++` directory of a ydClient SDK, which contains include/, linux64/, win64/ and error_code.csv
+pub struct YdSdk {
+    pub dir: PathBuf,
+    pub version: String,
+}
+
+impl YdSdk {
+    pub fn include_dir(&self) -> PathBuf {
+        self.dir.join("include")
+    }
+}
+
+/// `YD_SDK_DIR` takes precedence over the `sdk-*` features, its version is read from `YD_SDK_VERSION`,
+/// or from the directory name like `ydClient_1_386_40_0/ydAPI_c++`
+pub fn resolve_sdk(third_party_dir: &Path) -> YdSdk {
+    println!("cargo:rerun-if-env-changed=YD_SDK_DIR");
+    println!("cargo:rerun-if-env-changed=YD_SDK_VERSION");
+    if let Some(dir) = env::var_os("YD_SDK_DIR") {
+        let dir = PathBuf::from(dir);
+        assert!(dir.join("include").is_dir(), "YD_SDK_DIR should contain include/: {}", dir.display());
+        let version = env::var("YD_SDK_VERSION")
+            .ok()
+            .or_else(|| find_version_in_path(&dir))
+            .unwrap_or_else(|| panic!("Can't find SDK version in {}, please set YD_SDK_VERSION", dir.display()));
+        return YdSdk { dir, version };
+    }
+    let selected: Vec<_> = BUNDLED_SDKS
+        .iter()
+        .filter(|(feature, _, _)| env::var_os(feature).is_some())
+        .collect();
+    let (_, version, relative_dir) = match selected.as_slice() {
+        [] => BUNDLED_SDKS.last().unwrap(),
+        [sdk] => sdk,
+        _ => panic!("Only one sdk-* feature can be enabled"),
+    };
+    YdSdk {
+        dir: third_party_dir.join(relative_dir),
+        version: version.to_string(),
+    }
+}
+
+/// `ydClient_1_386_40_0` -> `1.386.40.0`, the name of the archive downloaded from ydClient
+fn find_version_in_path(dir: &Path) -> Option<String> {
+    dir.components().rev().find_map(|component| {
+        let numbers: Vec<&str> = component
+            .as_os_str()
+            .to_str()?
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|number| !number.is_empty())
+            .collect();
+        (numbers.len() == 4).then(|| numbers.join("."))
+    })
+}
@@ -0,0 +1,78 @@
+use inflector::Inflector;
+
+use super::data_struct::parse_data_struct_classes;
+
+/// `typedef char YDInstrumentID[32];` in ydDataType.h -> (`YDInstrumentID`, 32)
+pub fn parse_string_typedefs(data_type_header: &str) -> Vec<(String, usize)> {
+    data_type_header
+        .lines()
+        .filter_map(|line| {
+            let (name, length) = line.trim().strip_prefix("typedef char ")?.strip_suffix("];")?.split_once('[')?;
+            Some((name.trim().to_string(), length.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+struct StringField {
+    class_name: String,
+    field_name: String,
+    type_name: String,
+    length: usize,
+}
+
+/// ydDataStruct.h 中类型为上面这些 typedef 的字段，例如 `YDInstrumentID InstrumentID;`。
+/// `YDTradingCode TradingCode[YD_MaxHedgeFlag];` 这样的数组不生成 setter
+fn parse_string_fields(data_struct_header: &str, typedefs: &[(String, usize)]) -> Vec<StringField> {
+    let mut fields = Vec::new();
+    for class in parse_data_struct_classes(data_struct_header) {
+        for field in class.fields.iter().filter(|field| !field.is_pointer && field.array.is_none()) {
+            if let Some((_, length)) = typedefs.iter().find(|(name, _)| *name == field.type_name) {
+                fields.push(StringField {
+                    class_name: class.name.clone(),
+                    field_name: field.name.clone(),
+                    type_name: field.type_name.clone(),
+                    length: *length,
+                });
+            }
+        }
+    }
+    fields
+}
+
+pub fn generate_string_fields(data_type_header: &str, data_struct_header: &str) -> String {
+    let typedefs = parse_string_typedefs(data_type_header);
+    let fields = parse_string_fields(data_struct_header, &typedefs);
+    let mut lines = vec![
+        "// length-checked setters of fixed-size string fields, generated from ydDataType.h and ydDataStruct.h\n\n".to_string(),
+        "use crate::{bindings::*, fixed_str::{set_fixed_str, FixedStrError}};\n".to_string(),
+    ];
+    let mut current_class: Option<&str> = None;
+    for field in &fields {
+        if current_class != Some(field.class_name.as_str()) {
+            if current_class.is_some() {
+                lines.push("}\n".to_string());
+            }
+            lines.push(format!("\nimpl {} {{\n", field.class_name));
+            current_class = Some(&field.class_name);
+        }
+        let StringField {
+            field_name,
+            type_name,
+            length,
+            ..
+        } = field;
+        let snake_field_name = Inflector::to_snake_case(field_name);
+        lines.push(format!(
+            r#"    /// `{field_name}` is `{type_name}`, `value` should not contain `\0` or exceed {max_len} bytes
+    pub fn set_{snake_field_name}(&mut self, value: &str) -> Result<(), FixedStrError> {{
+        set_fixed_str(&mut self.{field_name}, value)
+    }}
+"#,
+            max_len = length - 1
+        ));
+    }
+    if current_class.is_some() {
+        lines.push("}\n".to_string());
+    }
+    lines.join("")
+}
@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+
+/// error_code.csv 中的交易所名称和生成的 `ErrorExchange` 变体，以及对应的 `YDExchange::ExchangeID`
+const ERROR_EXCHANGES: [(&str, &str, &[&str]); 7] = [
+    ("易达", "Yd", &[]),
+    ("上期所/能源所", "ShfeIne", &["SHFE", "INE"]),
+    ("中金所", "Cffex", &["CFFEX"]),
+    ("大商所", "Dce", &["DCE"]),
+    ("郑商所", "Czce", &["CZCE"]),
+    ("上交所", "Sse", &["SSE"]),
+    ("深交所", "Szse", &["SZSE"]),
+];
+
+struct YdErrorConst {
+    name: String,
+    code: i32,
+}
+
+/// ydError.h 中的 `const int YD_ERROR_Name=code;`，不包括 `YD_ERROR_NoError`
+fn parse_error_consts(header: &str) -> Vec<YdErrorConst> {
+    header
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("const int YD_ERROR_"))
+        .map(|definition| {
+            let (name, code) = definition
+                .trim_end_matches(';')
+                .split_once('=')
+                .unwrap_or_else(|| panic!("Unexpected constant in ydError.h: {definition}"));
+            YdErrorConst {
+                name: name.trim().to_string(),
+                code: code.trim().parse().unwrap_or_else(|_| panic!("Unexpected value in ydError.h: {definition}")),
+            }
+        })
+        .filter(|c| c.code != 0)
+        .collect()
+}
+
+/// error_code.csv 的表头是 `Exchange,ErrorCode,ErrorMsg`，同一个交易所可能有重复的错误码，对应的信息用 `；` 连接
+fn parse_error_messages(csv_content: &str) -> BTreeMap<(usize, i32), String> {
+    let mut messages: BTreeMap<(usize, i32), String> = BTreeMap::new();
+    let mut reader = csv::Reader::from_reader(csv_content.trim_start_matches('\u{feff}').as_bytes());
+    for record in reader.records() {
+        let record = record.expect("Unable to parse error_code.csv");
+        // 新版本 SDK 可能支持更多交易所，先跳过，等加到 ERROR_EXCHANGES 里
+        let Some(exchange_index) = ERROR_EXCHANGES.iter().position(|(name, _, _)| *name == &record[0]) else {
+            println!("cargo:warning=Unexpected exchange in error_code.csv: {}", &record[0]);
+            continue;
+        };
+        let code: i32 = record[1]
+            .trim()
+            .parse()
+            .unwrap_or_else(|_| panic!("Unexpected error code in error_code.csv: {}", &record[1]));
+        let message = record[2].trim();
+        messages
+            .entry((exchange_index, code))
+            .and_modify(|existing| {
+                existing.push('；');
+                existing.push_str(message);
+            })
+            .or_insert_with(|| message.to_string());
+    }
+    messages
+}
+
+fn generate_error_exchange() -> String {
+    let variants = ERROR_EXCHANGES
+        .iter()
+        .map(|(name, variant, _)| format!("    /// {name}\n    {variant},\n"))
+        .collect::<String>();
+    let exchange_id_arms = ERROR_EXCHANGES
+        .iter()
+        .filter(|(_, _, exchange_ids)| !exchange_ids.is_empty())
+        .map(|(_, variant, exchange_ids)| {
+            let patterns = exchange_ids.iter().map(|id| format!("\"{id}\"")).collect::<Vec<_>>().join(" | ");
+            format!("            {patterns} => Some(Self::{variant}),\n")
+        })
+        .collect::<String>();
+    let name_arms = ERROR_EXCHANGES
+        .iter()
+        .map(|(name, variant, _)| format!("            Self::{variant} => \"{name}\",\n"))
+        .collect::<String>();
+    format!(
+        r#"
+/// the source of an error code, same as the `Exchange` column of error_code.csv
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ErrorExchange {{
+{variants}}}
+
+impl ErrorExchange {{
+    /// map `YDExchange::ExchangeID` to the exchange in error_code.csv, GFEX has no error messages yet
+    pub fn from_exchange_id(exchange_id: &str) -> Option<Self> {{
+        match exchange_id {{
+{exchange_id_arms}            _ => None,
+        }}
+    }}
+
+    pub fn name_zh(&self) -> &'static str {{
+        match self {{
+{name_arms}        }}
+    }}
+}}
+"#
+    )
+}
+
+fn generate_yd_error(consts: &[YdErrorConst], messages: &BTreeMap<(usize, i32), String>) -> String {
+    let exchange_report_error = consts
+        .iter()
+        .find(|c| c.name == "ExchangeReportError")
+        .expect("Can't find YD_ERROR_ExchangeReportError in ydError.h")
+        .code;
+    let yd_consts: Vec<&YdErrorConst> = consts.iter().filter(|c| c.code < exchange_report_error).collect();
+    let variants = yd_consts
+        .iter()
+        .map(|c| format!("    /// `YD_ERROR_{}`\n    {},\n", c.name, c.name))
+        .collect::<String>();
+    let from_code_arms = yd_consts
+        .iter()
+        .map(|c| format!("            {} => Self::{},\n", c.code, c.name))
+        .collect::<String>();
+    let code_arms = yd_consts
+        .iter()
+        .map(|c| format!("            Self::{} => {},\n", c.name, c.code))
+        .collect::<String>();
+    let message_rows = messages
+        .iter()
+        .map(|((exchange_index, code), message)| {
+            format!("    (ErrorExchange::{}, {code}, {message:?}),\n", ERROR_EXCHANGES[*exchange_index].1)
+        })
+        .collect::<String>();
+    let num_messages = messages.len();
+    format!(
+        r#"
+/// error numbers in callbacks like `notifyLogin` and fields like `YDOrder::ErrorNo`
+///
+/// - below {exchange_report_error}: generated by ydServer or ydAPI, see ydError.h
+/// - {exchange_report_error} and above: reported by exchanges, the same code means different things in different exchanges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum YdError {{
+{variants}    /// generated by ydServer or ydAPI, but not listed in ydError.h
+    Other(i32),
+    /// reported by exchange, `exchange` is `None` when it is unknown
+    Exchange {{ exchange: Option<ErrorExchange>, code: i32 }},
+}}
+
+impl YdError {{
+    /// `None` for `YD_ERROR_NoError`
+    pub fn from_code(code: i32) -> Option<Self> {{
+        Self::from_exchange_code(code, None)
+    }}
+
+    /// same as `from_code`, but remember the exchange to look up message of codes reported by exchanges
+    pub fn from_exchange_code(code: i32, exchange: Option<ErrorExchange>) -> Option<Self> {{
+        Some(match code {{
+            0 => return None,
+{from_code_arms}            code if code < {exchange_report_error} => Self::Other(code),
+            code => Self::Exchange {{ exchange, code }},
+        }})
+    }}
+
+    pub fn code(&self) -> i32 {{
+        match self {{
+{code_arms}            Self::Other(code) => *code,
+            Self::Exchange {{ code, .. }} => *code,
+        }}
+    }}
+
+    pub fn exchange(&self) -> Option<ErrorExchange> {{
+        match self {{
+            Self::Exchange {{ exchange, .. }} => *exchange,
+            _ => Some(ErrorExchange::Yd),
+        }}
+    }}
+
+    /// Chinese message from error_code.csv
+    pub fn message_zh(&self) -> Option<&'static str> {{
+        lookup_error_message(self.code(), self.exchange()?)
+    }}
+}}
+
+impl From<YdError> for i32 {{
+    fn from(error: YdError) -> Self {{
+        error.code()
+    }}
+}}
+
+impl fmt::Display for YdError {{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {{
+        let exchange = self.exchange().map_or("未知交易所", |exchange| exchange.name_zh());
+        match self.message_zh() {{
+            Some(message) => write!(f, "{{exchange}} 错误 {{}}: {{message}}", self.code()),
+            None => write!(f, "{{exchange}} 错误 {{}}", self.code()),
+        }}
+    }}
+}}
+
+impl std::error::Error for YdError {{}}
+
+/// resolve an error number to its message for a given exchange, use `ErrorExchange::Yd` for errors below {exchange_report_error}
+pub fn lookup_error_message(code: i32, exchange: ErrorExchange) -> Option<&'static str> {{
+    ERROR_MESSAGES
+        .binary_search_by(|(e, c, _)| (*e, *c).cmp(&(exchange, code)))
+        .ok()
+        .map(|index| ERROR_MESSAGES[index].2)
+}}
+
+/// sorted by exchange and code
+static ERROR_MESSAGES: [(ErrorExchange, i32, &str); {num_messages}] = [
+{message_rows}];
+"#
+    )
+}
+
+/// 从 ydError.h 和 error_code.csv 生成 `YdError`，以免在业务代码里直接处理 int 错误码
+pub fn generate_yd_error_file(header: &str, csv_content: &str) -> String {
+    let consts = parse_error_consts(header);
+    let messages = parse_error_messages(csv_content);
+    let mut lines = vec!["use std::fmt;\n".to_string()];
+    lines.push(format!("\n/* Generated by generate_error_exchange */"));
+    lines.push(generate_error_exchange());
+    lines.push(format!("\n/* Generated by generate_yd_error */"));
+    lines.push(generate_yd_error(&consts, &messages));
+    lines.join("")
+}
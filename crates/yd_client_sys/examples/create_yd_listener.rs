@@ -1,7 +1,6 @@
 // crates/yd_client_sys/examples/create_yd_listener.rs
 
-use std::ffi::CString;
-use yd_client_sys::{create_yd_api_and_spi, spi_wrapper::YDListenerOutput};
+use yd_client_sys::spi_wrapper::YDListenerOutput;
 use futures::StreamExt;
 use log::info;
 
@@ -17,16 +16,23 @@ async fn main() {
     let config_filename = "crates/yd_client_sys/examples/config.txt";
 
     // Create the API and SPI using the configuration file
-    let (mut api, mut spi_stream) = create_yd_api_and_spi(config_filename);
+    #[cfg(not(feature = "dlopen"))]
+    let (mut api, mut spi_stream) = yd_client_sys::create_yd_api_and_spi(config_filename);
+    // With the dlopen feature, libyd is loaded from the path given at runtime
+    #[cfg(feature = "dlopen")]
+    let (mut api, mut spi_stream) = {
+        let library = yd_client_sys::YdLibrary::load("crates/yd_client_sys/thirdparty/ydClient/ydAPI_c++/linux64/libyd.so")
+            .unwrap_or_else(|error| panic!("{error}"));
+        library.try_create_yd_api_and_spi(config_filename).unwrap_or_else(|error| panic!("{error}"))
+    };
 
-    // Convert your login details to CString
-    let username = CString::new("your_username").unwrap();
-    let password = CString::new("your_password").unwrap();
-    let app_id = CString::new("your_app_id").unwrap();
-    let auth_code = CString::new("your_auth_code").unwrap();
-
-    // Attempt to log in
-    if api.login(username, password, app_id, auth_code) {
+    // Attempt to log in, strings containing NUL are rejected before calling libyd
+    let logged_in = api
+        .api_mut()
+        .unwrap()
+        .login("your_username", "your_password", "your_app_id", "your_auth_code")
+        .expect("login details should not contain NUL");
+    if logged_in {
         info!("Login request sent successfully.");
     } else {
         info!("Failed to send login request.");
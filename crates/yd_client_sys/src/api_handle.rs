@@ -0,0 +1,378 @@
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+    sync::{Arc, Condvar, Mutex},
+};
+use tokio::sync::watch;
+
+use crate::{
+    api_wrapper::YDApiVTable,
+    bindings::{YDApi, YDExtendedApi},
+    catalog::{Catalog, CatalogNotReady},
+    spi_wrapper::{YDExtendedListenerTrait, YDListenerTrait},
+};
+
+/// progress of `startDestroy`, see ydApi.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiDestroyPhase {
+    Running,
+    /// `startDestroy` is called, trading functions can not be used any more
+    DestroyStarted,
+    /// `notifyBeforeApiDestroy` is received, all data pointers given by api are going to be invalid
+    BeforeApiDestroy,
+    /// `notifyAfterApiDestroy` is received and the listener is released
+    AfterApiDestroy,
+}
+
+/// returned by `YDApiHandle::api_mut` once `startDestroy` is called
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApiDestroyedError;
+
+impl fmt::Display for ApiDestroyedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "api is being destroyed")
+    }
+}
+
+impl std::error::Error for ApiDestroyedError {}
+
+/// C++ classes created by `makeYDApi` or `makeYDExtendedApi`
+pub trait ApiRecord {
+    fn as_yd_api(&mut self) -> &mut YDApi;
+}
+
+impl ApiRecord for YDApi {
+    fn as_yd_api(&mut self) -> &mut YDApi {
+        self
+    }
+}
+
+impl ApiRecord for YDExtendedApi {
+    fn as_yd_api(&mut self) -> &mut YDApi {
+        &mut self._base
+    }
+}
+
+/// the api pointer used by the `notifyEvent` hook on the libyd thread, the api is never deleted
+struct RestartedApi(NonNull<YDApi>);
+
+unsafe impl Send for RestartedApi {}
+
+impl RestartedApi {
+    /// call `startDestroy` through the v-table, without making a `&mut YDApi` that the user may hold at the same time
+    unsafe fn start_destroy(&self) {
+        let this = self.0.as_ptr();
+        let vtable = &**(this as *const *const YDApiVTable);
+        (vtable.YDApi_startDestroy)(this);
+    }
+}
+
+/// number of `ApiMut` and `Catalog` alive. `startDestroy` called by the `notifyEvent` hook runs on the libyd thread
+/// while the handle may be borrowed, so `notifyBeforeApiDestroy` waits until they are released
+#[derive(Default)]
+struct DataBorrows {
+    count: Mutex<usize>,
+    released: Condvar,
+}
+
+impl DataBorrows {
+    /// `None` once `startDestroy` is called, the phase is checked under the lock, see `wait_released`
+    fn borrow(self: &Arc<Self>, phase: &watch::Receiver<ApiDestroyPhase>) -> Option<DataBorrow> {
+        let mut count = self.count.lock().unwrap();
+        if *phase.borrow() != ApiDestroyPhase::Running {
+            return None;
+        }
+        *count += 1;
+        Some(DataBorrow(self.clone()))
+    }
+
+    /// the phase is already past `Running`, so no new borrow can be made
+    fn wait_released(&self) {
+        let count = self.count.lock().unwrap();
+        drop(self.released.wait_while(count, |count| *count > 0).unwrap());
+    }
+}
+
+/// set by the listener on the libyd thread after `notifyAfterApiDestroy`, drop of the handle waits for it without
+/// an async executor, so it works inside or outside a runtime
+#[derive(Default)]
+struct AfterApiDestroy {
+    done: Mutex<bool>,
+    notified: Condvar,
+}
+
+impl AfterApiDestroy {
+    fn notify(&self) {
+        *self.done.lock().unwrap() = true;
+        self.notified.notify_all();
+    }
+
+    fn wait(&self) {
+        let done = self.done.lock().unwrap();
+        drop(self.notified.wait_while(done, |done| !*done).unwrap());
+    }
+}
+
+/// a borrow of data owned by libyd, `notifyBeforeApiDestroy` waits until it is dropped
+pub(crate) struct DataBorrow(Arc<DataBorrows>);
+
+impl Clone for DataBorrow {
+    fn clone(&self) -> Self {
+        *self.0.count.lock().unwrap() += 1;
+        Self(self.0.clone())
+    }
+}
+
+impl Drop for DataBorrow {
+    fn drop(&mut self) {
+        let mut count = self.0.count.lock().unwrap();
+        *count -= 1;
+        if *count == 0 {
+            self.0.released.notify_all();
+        }
+    }
+}
+
+/// the api returned by `YDApiHandle::api_mut`. Query results and strings returned by the api borrow it,
+/// so they are released before the api is destroyed, even if `startDestroy` is called by the `notifyEvent` hook
+pub struct ApiMut<'h, A> {
+    api: &'h mut A,
+    _borrow: DataBorrow,
+}
+
+impl<A> Deref for ApiMut<'_, A> {
+    type Target = A;
+
+    fn deref(&self) -> &A {
+        self.api
+    }
+}
+
+impl<A> DerefMut for ApiMut<'_, A> {
+    fn deref_mut(&mut self) -> &mut A {
+        self.api
+    }
+}
+
+/// owns an api created by libyd. The api is never deleted, as ydApi.h requires.
+///
+/// Dropping a handle calls `startDestroy`, and if the api is started, blocks the thread until `notifyAfterApiDestroy`,
+/// use `shutdown` in async code instead. Don't drop it inside a listener callback, which would wait for itself.
+pub struct YDApiHandle<A: ApiRecord> {
+    api: NonNull<A>,
+    started: bool,
+    phase_sender: watch::Sender<ApiDestroyPhase>,
+    phase: watch::Receiver<ApiDestroyPhase>,
+    borrows: Arc<DataBorrows>,
+    after_destroy: Arc<AfterApiDestroy>,
+    destroy_on_server_restart: bool,
+    /// libyd loaded at runtime must not be unloaded while the api is alive
+    #[cfg(feature = "dlopen")]
+    library: Option<crate::YdLibrary>,
+}
+
+unsafe impl<A: ApiRecord> Send for YDApiHandle<A> {}
+
+impl<A: ApiRecord> YDApiHandle<A> {
+    /// # Safety
+    ///
+    /// `api_ptr` must come from `makeYDApi` or `makeYDExtendedApi`, and not be owned by others
+    pub unsafe fn from_raw(api_ptr: *mut A) -> Option<Self> {
+        let (phase_sender, phase) = watch::channel(ApiDestroyPhase::Running);
+        Some(Self {
+            api: NonNull::new(api_ptr)?,
+            started: false,
+            phase_sender,
+            phase,
+            borrows: Arc::default(),
+            after_destroy: Arc::default(),
+            destroy_on_server_restart: false,
+            #[cfg(feature = "dlopen")]
+            library: None,
+        })
+    }
+
+    #[cfg(feature = "dlopen")]
+    pub(crate) fn keep_library(mut self, library: crate::YdLibrary) -> Self {
+        self.library = Some(library);
+        self
+    }
+
+    /// the api, until `startDestroy` is called
+    pub fn api_mut(&mut self) -> Result<ApiMut<'_, A>, ApiDestroyedError> {
+        let borrow = self.borrows.borrow(&self.phase).ok_or(ApiDestroyedError)?;
+        Ok(ApiMut {
+            api: unsafe { self.api.as_mut() },
+            _borrow: borrow,
+        })
+    }
+
+    /// static data of the api, available after `notifyFinishInit` until `startDestroy` is called
+    pub fn catalog(&self) -> Result<Catalog<'_>, CatalogNotReady> {
+        unsafe { self.unbound_catalog() }
+    }
+
+    /// the api and its catalog at the same time, so references from the catalog can be passed to trading methods:
+    ///
+    /// ```ignore
+    /// let (mut api, catalog) = handle.api_and_catalog()?;
+    /// let instrument = catalog.instrument("cu2401").unwrap();
+    /// let account = catalog.my_account().unwrap();
+    /// api.insert_order(&mut input_order, instrument, account);
+    /// ```
+    pub fn api_and_catalog(&mut self) -> Result<(ApiMut<'_, A>, Catalog<'_>), CatalogNotReady> {
+        // catalog 只保存裸指针，不借用 api 结构体本身，它的生命周期由返回值绑定到 &mut self
+        let catalog = unsafe { self.unbound_catalog() }?;
+        Ok((self.api_mut().map_err(|_| CatalogNotReady)?, catalog))
+    }
+
+    /// # Safety
+    ///
+    /// the caller must bind `'c` to a borrow of this handle, so the api is not destroyed during `'c`
+    unsafe fn unbound_catalog<'c>(&self) -> Result<Catalog<'c>, CatalogNotReady> {
+        let borrow = self.borrows.borrow(&self.phase).ok_or(CatalogNotReady)?;
+        // YDExtendedApi 单继承 YDApi，基类子对象在偏移 0 处
+        Catalog::new(self.api.as_ptr().cast::<YDApi>(), borrow).ok_or(CatalogNotReady)
+    }
+
+    pub fn destroy_phase(&self) -> ApiDestroyPhase {
+        *self.phase.borrow()
+    }
+
+    /// tell the listener to report `notifyBeforeApiDestroy` and `notifyAfterApiDestroy` to this handle
+    fn on_destroy_phase(&self) -> Box<dyn Fn(ApiDestroyPhase) + Send> {
+        let phase_sender = self.phase_sender.clone();
+        let borrows = self.borrows.clone();
+        let after_destroy = self.after_destroy.clone();
+        Box::new(move |phase| {
+            if phase == ApiDestroyPhase::BeforeApiDestroy {
+                // notifyEvent 中调用的 startDestroy 不经过 &mut self，用户可能还持有 catalog 或者 api，等它们释放后再让数据失效
+                borrows.wait_released();
+            }
+            phase_sender.send_replace(phase);
+            if phase == ApiDestroyPhase::AfterApiDestroy {
+                after_destroy.notify();
+            }
+        })
+    }
+
+    /// call `startDestroy` inside `notifyEvent(YD_AE_ServerRestarted)`, after the listener gets the event.
+    /// Otherwise libyd exits the process once the callback returns, see ydApi.h. It is off by default,
+    /// and must be set before `start`. The handle then goes to `DestroyStarted`, shut it down and create a new api.
+    /// `notifyBeforeApiDestroy` waits until `ApiMut` and `Catalog` borrowed from the handle are dropped, don't hold them for long
+    pub fn set_destroy_on_server_restart(&mut self, destroy_on_server_restart: bool) {
+        self.destroy_on_server_restart = destroy_on_server_restart;
+    }
+
+    /// `startDestroy` inside `notifyEvent`, if `destroy_on_server_restart` is set
+    fn on_server_restarted(&self) -> Option<Box<dyn Fn() + Send>> {
+        if !self.destroy_on_server_restart {
+            return None;
+        }
+        // YDExtendedApi 单继承 YDApi，基类子对象在偏移 0 处
+        let api = RestartedApi(self.api.cast::<YDApi>());
+        let phase_sender = self.phase_sender.clone();
+        Some(Box::new(move || {
+            if begin_destroy(&phase_sender) {
+                unsafe { api.start_destroy() };
+            }
+        }))
+    }
+
+    /// the listener is called on the libyd thread, so it must be `Send`. It must implement the trait for every lifetime,
+    /// since the data passed to callbacks is reused or freed by libyd once the callback returns
+    pub fn start(&mut self, listener: Box<dyn for<'a> YDListenerTrait<'a> + Send>) -> Result<bool, ApiDestroyedError> {
+        let on_destroy_phase = self.on_destroy_phase();
+        let on_server_restarted = self.on_server_restarted();
+        let started = self
+            .api_mut()?
+            .as_yd_api()
+            .start(listener, Some(on_destroy_phase), on_server_restarted);
+        self.started |= started;
+        Ok(started)
+    }
+
+    /// register a listener implemented by the caller, its methods are called synchronously on the libyd thread,
+    /// without queueing. The listener is dropped after `notifyAfterApiDestroy`, before `shutdown` or drop of this handle returns.
+    pub fn start_with_listener<L>(&mut self, listener: Box<L>) -> Result<bool, ApiDestroyedError>
+    where
+        L: for<'a> YDListenerTrait<'a> + Send + 'static,
+    {
+        self.start(listener)
+    }
+
+    /// call `startDestroy` only once, also for an api that is never started, returns whether there is a listener to wait for
+    fn start_destroy(&mut self) -> bool {
+        if begin_destroy(&self.phase_sender) {
+            unsafe { self.api.as_mut() }.as_yd_api().start_destroy();
+        }
+        self.started
+    }
+
+    /// call `startDestroy`, and wait until `notifyAfterApiDestroy` is received and the listener is released
+    pub async fn shutdown(mut self) {
+        if self.start_destroy() {
+            // the sender is owned by self, so it is never closed here
+            let _ = self.phase.wait_for(|phase| *phase >= ApiDestroyPhase::BeforeApiDestroy).await;
+            let _ = self.phase.wait_for(|phase| *phase == ApiDestroyPhase::AfterApiDestroy).await;
+        }
+    }
+}
+
+impl YDApiHandle<YDExtendedApi> {
+    /// both listeners are called on the libyd thread, so they must be `Send`
+    pub fn start_extended(
+        &mut self,
+        listener: Box<dyn for<'a> YDListenerTrait<'a> + Send>,
+        extended_listener: Box<dyn for<'a> YDExtendedListenerTrait<'a> + Send>,
+    ) -> Result<bool, ApiDestroyedError> {
+        let on_destroy_phase = self.on_destroy_phase();
+        let on_server_restarted = self.on_server_restarted();
+        let started = self.api_mut()?.start_extended(
+            listener,
+            extended_listener,
+            Some(on_destroy_phase),
+            on_server_restarted,
+        );
+        self.started |= started;
+        Ok(started)
+    }
+
+    /// same as `start_with_listener`, and the extended listener is dropped together with the listener
+    pub fn start_extended_with_listeners<L, E>(
+        &mut self,
+        listener: Box<L>,
+        extended_listener: Box<E>,
+    ) -> Result<bool, ApiDestroyedError>
+    where
+        L: for<'a> YDListenerTrait<'a> + Send + 'static,
+        E: for<'a> YDExtendedListenerTrait<'a> + Send + 'static,
+    {
+        self.start_extended(listener, extended_listener)
+    }
+}
+
+/// `Running` -> `DestroyStarted`, returns whether the caller should call `startDestroy`.
+/// Both the handle and the `notifyEvent` hook may try it, only one of them wins
+fn begin_destroy(phase_sender: &watch::Sender<ApiDestroyPhase>) -> bool {
+    phase_sender.send_if_modified(|phase| {
+        let running = *phase == ApiDestroyPhase::Running;
+        if running {
+            *phase = ApiDestroyPhase::DestroyStarted;
+        }
+        running
+    })
+}
+
+impl<A: ApiRecord> Drop for YDApiHandle<A> {
+    fn drop(&mut self) {
+        // api that is never started has no listener to notify us, startDestroy is enough
+        if self.start_destroy() {
+            self.after_destroy.wait();
+        }
+        // libyd 的线程在 startDestroy 或 notifyAfterApiDestroy 返回后可能还在运行，永远不卸载 libyd
+        #[cfg(feature = "dlopen")]
+        std::mem::forget(self.library.take());
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    ffi::CString,
+    fmt,
+    iter::FusedIterator,
+    marker::PhantomData,
+    os::raw::c_int,
+    ptr,
+};
+
+use crate::{api_handle::DataBorrow, api_wrapper::YDApiVTable, bindings::*, pre_trade::PreTradeCheck};
+
+/// returned by `YDApiHandle::catalog` before `notifyFinishInit`, or once `startDestroy` is called
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatalogNotReady;
+
+impl fmt::Display for CatalogNotReady {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "catalog is not ready, wait for notifyFinishInit")
+    }
+}
+
+impl std::error::Error for CatalogNotReady {}
+
+/// exchanges, products, instruments, accounts and other static data of an initialized api.
+///
+/// It doesn't borrow the api struct, only the `YDApiHandle`, so the references can be passed to
+/// trading methods, see `YDApiHandle::api_and_catalog`. The data is fixed after `notifyFinishInit`
+/// and is valid until the api is destroyed. References returned borrow the catalog, the api is not destroyed
+/// until it is dropped, even if `startDestroy` is called inside `notifyEvent`.
+#[derive(Clone)]
+pub struct Catalog<'a> {
+    this: *mut YDApi,
+    vtable: &'static YDApiVTable,
+    _borrow: DataBorrow,
+    _api: PhantomData<&'a YDApi>,
+}
+
+macro_rules! catalog_list {
+    ($(#[$doc: meta])* $name: ident, $count: ident, $get: ident, $item: ty) => {
+        $(#[$doc])*
+        pub fn $name(&self) -> CatalogIter<'_, $item> {
+            let count = unsafe { (self.vtable.$count)(self.this) };
+            CatalogIter {
+                this: self.this,
+                get: self.vtable.$get,
+                pos: 0,
+                count: count.max(0),
+                _api: PhantomData,
+            }
+        }
+    };
+}
+
+macro_rules! catalog_lookup {
+    ($(#[$doc: meta])* $name: ident, $get: ident, $item: ty) => {
+        $(#[$doc])*
+        pub fn $name(&self, id: &str) -> Option<&$item> {
+            // id containing NUL can't be found
+            let id = CString::new(id).ok()?;
+            unsafe { (self.vtable.$get)(self.this, id.as_ptr()).as_ref() }
+        }
+    };
+}
+
+impl Catalog<'_> {
+    /// # Safety
+    ///
+    /// `this` must be a started api, and not be destroyed while the catalog is alive
+    pub(crate) unsafe fn new(this: *mut YDApi, borrow: DataBorrow) -> Option<Self> {
+        // v-table pointer is the first field of C++ object, it never changes
+        let vtable = &**(this as *const *const YDApiVTable);
+        (vtable.YDApi_hasFinishedInit)(this).then_some(Self {
+            this,
+            vtable,
+            _borrow: borrow,
+            _api: PhantomData,
+        })
+    }
+
+    catalog_list!(system_params, YDApi_getSystemParamCount, YDApi_getSystemParam, YDSystemParam);
+    catalog_list!(exchanges, YDApi_getExchangeCount, YDApi_getExchange, YDExchange);
+    catalog_list!(products, YDApi_getProductCount, YDApi_getProduct, YDProduct);
+    catalog_list!(instruments, YDApi_getInstrumentCount, YDApi_getInstrument, YDInstrument);
+    catalog_list!(comb_position_defs, YDApi_getCombPositionDefCount, YDApi_getCombPositionDef, YDCombPositionDef);
+    catalog_list!(
+        /// only available to monitors, traders should use `my_account`
+        accounts,
+        YDApi_getAccountCount,
+        YDApi_getAccount,
+        YDAccount
+    );
+    catalog_list!(pre_positions, YDApi_getPrePositionCount, YDApi_getPrePosition, YDPrePosition);
+    catalog_list!(pre_holdings, YDApi_getPreHoldingCount, YDApi_getPreHolding, YDPreHolding);
+    catalog_list!(spot_pre_positions, YDApi_getSpotPrePositionCount, YDApi_getSpotPrePosition, YDSpotPrePosition);
+    catalog_list!(margin_rates, YDApi_getMarginRateCount, YDApi_getMarginRate, YDMarginRate);
+    catalog_list!(commission_rates, YDApi_getCommissionRateCount, YDApi_getCommissionRate, YDCommissionRate);
+    catalog_list!(
+        cash_commission_rates,
+        YDApi_getCashCommissionRateCount,
+        YDApi_getCashCommissionRate,
+        YDCashCommissionRate
+    );
+    catalog_list!(
+        message_commission_rates,
+        YDApi_getMessageCommissionRateCount,
+        YDApi_getMessageCommissionRate,
+        YDMessageCommissionRate
+    );
+    catalog_list!(margin_model_params, YDApi_getMarginModelParamCount, YDApi_getMarginModelParam, YDMarginModelParam);
+    catalog_list!(general_risk_params, YDApi_getGeneralRiskParamCount, YDApi_getGeneralRiskParam, YDGeneralRiskParam);
+
+    catalog_lookup!(exchange, YDApi_getExchangeByID, YDExchange);
+    catalog_lookup!(product, YDApi_getProductByID, YDProduct);
+    catalog_lookup!(instrument, YDApi_getInstrumentByID, YDInstrument);
+    catalog_lookup!(
+        /// only available to monitors, traders should use `my_account`
+        account,
+        YDApi_getAccountByID,
+        YDAccount
+    );
+
+    pub fn system_param(&self, name: &str, target: &str) -> Option<&YDSystemParam> {
+        let (name, target) = (CString::new(name).ok()?, CString::new(target).ok()?);
+        unsafe { (self.vtable.YDApi_getSystemParamByName)(self.this, name.as_ptr(), target.as_ptr()).as_ref() }
+    }
+
+    /// `comb_hedge_flag` refers to `CombHedgeFlag`
+    pub fn comb_position_def(&self, comb_position_id: &str, comb_hedge_flag: c_int) -> Option<&YDCombPositionDef> {
+        let comb_position_id = CString::new(comb_position_id).ok()?;
+        unsafe {
+            (self.vtable.YDApi_getCombPositionDefByID)(self.this, comb_position_id.as_ptr(), comb_hedge_flag).as_ref()
+        }
+    }
+
+    /// only available to traders
+    pub fn my_account(&self) -> Option<&YDAccount> {
+        unsafe { (self.vtable.YDApi_getMyAccount)(self.this).as_ref() }
+    }
+
+    /// `account` should be `None` for traders
+    pub fn account_exchange_info(
+        &self,
+        exchange: &YDExchange,
+        account: Option<&YDAccount>,
+    ) -> Option<&YDAccountExchangeInfo> {
+        let account = account.map_or(ptr::null(), |account| account as *const YDAccount);
+        unsafe { (self.vtable.YDApi_getAccountExchangeInfo)(self.this, exchange, account).as_ref() }
+    }
+
+    /// `account` should be `None` for traders
+    pub fn account_product_info(
+        &self,
+        product: &YDProduct,
+        account: Option<&YDAccount>,
+    ) -> Option<&YDAccountProductInfo> {
+        let account = account.map_or(ptr::null(), |account| account as *const YDAccount);
+        unsafe { (self.vtable.YDApi_getAccountProductInfo)(self.this, product, account).as_ref() }
+    }
+
+    /// `account` should be `None` for traders
+    pub fn account_instrument_info(
+        &self,
+        instrument: &YDInstrument,
+        account: Option<&YDAccount>,
+    ) -> Option<&YDAccountInstrumentInfo> {
+        let account = account.map_or(ptr::null(), |account| account as *const YDAccount);
+        unsafe { (self.vtable.YDApi_getAccountInstrumentInfo)(self.this, instrument, account).as_ref() }
+    }
+
+    /// `PreTradeCheck` of the instrument with `TradingRight` and `TradingConstraints` of the trader's own account,
+    /// `None` if the instrument is not found
+    pub fn pre_trade_check(&self, instrument_id: &str) -> Option<PreTradeCheck<'_>> {
+        let instrument = self.instrument(instrument_id)?;
+        // instrument 由 libyd 给出，m_pMarketData 在 catalog 存活期间有效
+        let mut check = unsafe { PreTradeCheck::new(instrument) };
+        check.account_instrument_info = self.account_instrument_info(instrument, None);
+        Some(check)
+    }
+}
+
+/// `getXXX(pos)` for `pos` in `0..getXXXCount()`
+pub struct CatalogIter<'a, T> {
+    this: *mut YDApi,
+    get: unsafe extern "C" fn(this: *mut YDApi, pos: c_int) -> *const T,
+    pos: c_int,
+    count: c_int,
+    _api: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for CatalogIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        while self.pos < self.count {
+            self.pos += 1;
+            // 按位置取出的对象不应为空，万一为空就跳过
+            if let Some(item) = unsafe { (self.get)(self.this, self.pos - 1).as_ref() } {
+                return Some(item);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some((self.count - self.pos) as usize))
+    }
+}
+
+impl<T> FusedIterator for CatalogIter<'_, T> {}
@@ -0,0 +1,49 @@
+use std::{ffi::NulError, fmt, io, path::PathBuf};
+
+/// reasons why `try_create_yd_api` and its siblings fail
+#[derive(Debug)]
+pub enum CreateApiError {
+    /// config file name contains `\0`, so it can't be passed to C++
+    InteriorNul(NulError),
+    ConfigNotFound(PathBuf),
+    ConfigUnreadable { path: PathBuf, source: io::Error },
+    /// `makeYDApi` or `makeYDExtendedApi` returns null, usually because the config file is invalid
+    NullApi,
+    /// `start` or `startExtended` returns false
+    StartFailed,
+    /// the loaded libyd is not the SDK selected at build time, see `check_yd_version`
+    VersionMismatch { expected: &'static str, actual: String },
+}
+
+impl fmt::Display for CreateApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InteriorNul(error) => write!(f, "config file name contains NUL: {error}"),
+            Self::ConfigNotFound(path) => write!(f, "config file not found: {}", path.display()),
+            Self::ConfigUnreadable { path, source } => {
+                write!(f, "config file {} is not readable: {source}", path.display())
+            }
+            Self::NullApi => write!(f, "failed to create api instance, get null pointer"),
+            Self::StartFailed => write!(f, "failed to start api"),
+            Self::VersionMismatch { expected, actual } => {
+                write!(f, "libyd version {actual} does not match the SDK {expected} used at build time")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CreateApiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InteriorNul(error) => Some(error),
+            Self::ConfigUnreadable { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<NulError> for CreateApiError {
+    fn from(error: NulError) -> Self {
+        Self::InteriorNul(error)
+    }
+}
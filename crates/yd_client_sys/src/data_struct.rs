@@ -0,0 +1,255 @@
+//! inline helpers of the C++ classes in ydDataStruct.h, the other accessors are generated into accessors.rs
+//!
+//! Helpers following pointers are unsafe like the generated `m_pXXX` accessors: the pointers must be null or valid,
+//! they are valid in structs given by libyd until the api is destroyed
+
+use std::slice;
+
+use crate::{bindings::*, data_types::OrderFlag};
+
+macro_rules! market_data_getter {
+    ($name: ident, $cpp_name: literal, $field: ident, $ty: ty) => {
+        #[doc = concat!("`", $cpp_name, "`, `None` if `m_pMarketData` is null")]
+        ///
+        /// # Safety
+        ///
+        /// `m_pMarketData` must be null or valid
+        pub unsafe fn $name(&self) -> Option<$ty> {
+            self.market_data().map(|market_data| market_data.$field)
+        }
+    };
+}
+
+impl YDInstrument {
+    market_data_getter!(get_pre_settlement_price, "getPreSettlementPrice", PreSettlementPrice, f64);
+    market_data_getter!(get_pre_close_price, "getPreClosePrice", PreClosePrice, f64);
+    market_data_getter!(get_pre_open_interest, "getPreOpenInterest", PreOpenInterest, f64);
+    market_data_getter!(get_upper_limit_price, "getUpperLimitPrice", UpperLimitPrice, f64);
+    market_data_getter!(get_lower_limit_price, "getLowerLimitPrice", LowerLimitPrice, f64);
+    market_data_getter!(get_last_price, "getLastPrice", LastPrice, f64);
+    market_data_getter!(get_bid_price, "getBidPrice", BidPrice, f64);
+    market_data_getter!(get_ask_price, "getAskPrice", AskPrice, f64);
+    market_data_getter!(get_bid_volume, "getBidVolume", BidVolume, i32);
+    market_data_getter!(get_ask_volume, "getAskVolume", AskVolume, i32);
+    market_data_getter!(get_turnover, "getTurnover", Turnover, f64);
+    market_data_getter!(get_open_interest, "getOpenInterest", OpenInterest, f64);
+    market_data_getter!(get_volume, "getVolume", Volume, i32);
+    market_data_getter!(get_average_price, "getAveragePrice", AveragePrice, f64);
+}
+
+impl YDExchange {
+    /// `ConnectionInfos`, an array of `ConnectionCount` elements
+    ///
+    /// # Safety
+    ///
+    /// `ConnectionInfos` must be null or point to `ConnectionCount` elements
+    pub unsafe fn connection_infos(&self) -> &[YDExchangeConnectionInfo] {
+        if self.ConnectionInfos.is_null() || self.ConnectionCount <= 0 {
+            return &[];
+        }
+        slice::from_raw_parts(self.ConnectionInfos, self.ConnectionCount as usize)
+    }
+}
+
+impl YDExtendedOrder {
+    /// `m_pInstrument`, `None` for orders of `YD_YOF_CombPosition`
+    ///
+    /// # Safety
+    ///
+    /// `m_pInstrument` must be null or valid
+    pub unsafe fn instrument(&self) -> Option<&YDInstrument> {
+        // m_pInstrument 和 m_pCombPositionDef 是同一个 union
+        if self.yd_order_flag() == Ok(OrderFlag::CombPosition) {
+            return None;
+        }
+        self.__bindgen_anon_1.m_pInstrument.as_ref()
+    }
+
+    /// `m_pCombPositionDef`, only for orders of `YD_YOF_CombPosition`
+    ///
+    /// # Safety
+    ///
+    /// `m_pCombPositionDef` must be null or valid
+    pub unsafe fn comb_position_def(&self) -> Option<&YDCombPositionDef> {
+        if self.yd_order_flag() != Ok(OrderFlag::CombPosition) {
+            return None;
+        }
+        self.__bindgen_anon_1.m_pCombPositionDef.as_ref()
+    }
+}
+
+impl YDExtendedAccount {
+    /// `usable`
+    pub fn usable(&self) -> f64 {
+        if self.PositionProfit > 0.0 {
+            self.Available
+        } else {
+            self.Available + self.PositionProfit
+        }
+    }
+
+    /// `canUse`
+    pub fn can_use(&self, value: f64) -> bool {
+        self.usable() > value
+    }
+
+    /// `staticCashBalance`, `None` if `m_pAccount` is null
+    ///
+    /// # Safety
+    ///
+    /// `m_pAccount` must be null or valid
+    pub unsafe fn static_cash_balance(&self) -> Option<f64> {
+        let account = self.account()?;
+        Some(account.PreBalance + account.Deposit - account.Withdraw)
+    }
+
+    /// `dynamicCashBalance`
+    pub fn dynamic_cash_balance(&self) -> f64 {
+        self.CloseProfit + self.PositionProfit + self.CashIn - self.Commission
+    }
+
+    /// `cashBalance`
+    ///
+    /// # Safety
+    ///
+    /// see `static_cash_balance`
+    pub unsafe fn cash_balance(&self) -> Option<f64> {
+        Some(self.static_cash_balance()? * self.account()?.MaxMoneyUsage + self.dynamic_cash_balance())
+    }
+
+    /// `marketValue`
+    ///
+    /// # Safety
+    ///
+    /// see `static_cash_balance`
+    pub unsafe fn market_value(&self) -> Option<f64> {
+        Some(self.cash_balance()? + self.PositionMarketValue)
+    }
+
+    /// `preCashBalance`
+    ///
+    /// # Safety
+    ///
+    /// see `static_cash_balance`
+    pub unsafe fn pre_cash_balance(&self) -> Option<f64> {
+        let account = self.account()?;
+        Some(account.PreBalance * account.MaxMoneyUsage)
+    }
+
+    /// `preMarketValue`
+    ///
+    /// # Safety
+    ///
+    /// see `static_cash_balance`
+    pub unsafe fn pre_market_value(&self) -> Option<f64> {
+        Some(self.pre_cash_balance()? + self.PrePositionMarketValue)
+    }
+}
+
+impl YDExtendedPosition {
+    /// `getAccount`, `None` if a pointer on the way is null
+    ///
+    /// # Safety
+    ///
+    /// `m_pAccountInstrumentInfo` and its `m_pAccount` must be null or valid
+    pub unsafe fn get_account(&self) -> Option<&YDAccount> {
+        self.account_instrument_info()?.account()
+    }
+
+    /// `getInstrument`, `None` if a pointer on the way is null
+    ///
+    /// # Safety
+    ///
+    /// `m_pAccountInstrumentInfo` and its `m_pInstrument` must be null or valid
+    pub unsafe fn get_instrument(&self) -> Option<&YDInstrument> {
+        self.account_instrument_info()?.instrument()
+    }
+
+    /// `getMarginRate`, `None` if `HedgeFlag` is out of range or the rate is not set
+    ///
+    /// # Safety
+    ///
+    /// `m_pAccountInstrumentInfo` and its `m_pMarginRate` must be null or valid
+    pub unsafe fn get_margin_rate(&self) -> Option<&YDMarginRate> {
+        let index = usize::try_from(self.HedgeFlag).ok()?.checked_sub(1)?;
+        self.account_instrument_info()?.m_pMarginRate.get(index)?.as_ref()
+    }
+
+    /// `getCommissionRate`, `None` if `HedgeFlag` is out of range or the rate is not set
+    ///
+    /// # Safety
+    ///
+    /// `m_pAccountInstrumentInfo` and its `m_pCommissionRate` must be null or valid
+    pub unsafe fn get_commission_rate(&self) -> Option<&YDCommissionRate> {
+        let index = usize::try_from(self.HedgeFlag).ok()?.checked_sub(1)?;
+        self.account_instrument_info()?.m_pCommissionRate.get(index)?.as_ref()
+    }
+
+    /// `getOpenPrice`, 0 if there is no position
+    pub fn get_open_price(&self) -> f64 {
+        if self.Position > 0 {
+            self.TotalOpenPrice / self.Position as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// `getYDPosition`, yesterday position calculated according to "first open, first close" rule
+    ///
+    /// # Safety
+    ///
+    /// `PositionDetailList` must be a null terminated list of valid details
+    pub unsafe fn get_yd_position(&self) -> i32 {
+        let mut position = 0;
+        let mut detail = self.PositionDetailList;
+        // 历史持仓的 TradeID 小于 0，排在明细列表的最前面
+        while let Some(current) = detail.as_ref() {
+            if current.__bindgen_anon_1.LongTradeID >= 0 {
+                break;
+            }
+            position += current.Volume;
+            detail = current.m_pNext;
+        }
+        position
+    }
+}
+
+impl YDExtendedHolding {
+    /// `getAccount`, `None` if a pointer on the way is null
+    ///
+    /// # Safety
+    ///
+    /// `m_pAccountInstrumentInfo` and its `m_pAccount` must be null or valid
+    pub unsafe fn get_account(&self) -> Option<&YDAccount> {
+        self.account_instrument_info()?.account()
+    }
+
+    /// `getInstrument`, `None` if a pointer on the way is null
+    ///
+    /// # Safety
+    ///
+    /// `m_pAccountInstrumentInfo` and its `m_pInstrument` must be null or valid
+    pub unsafe fn get_instrument(&self) -> Option<&YDInstrument> {
+        self.account_instrument_info()?.instrument()
+    }
+}
+
+impl YDExtendedSpotPosition {
+    /// `getAccount`, `None` if a pointer on the way is null
+    ///
+    /// # Safety
+    ///
+    /// `m_pAccountInstrumentInfo` and its `m_pAccount` must be null or valid
+    pub unsafe fn get_account(&self) -> Option<&YDAccount> {
+        self.account_instrument_info()?.account()
+    }
+
+    /// `getInstrument`, `None` if a pointer on the way is null
+    ///
+    /// # Safety
+    ///
+    /// `m_pAccountInstrumentInfo` and its `m_pInstrument` must be null or valid
+    pub unsafe fn get_instrument(&self) -> Option<&YDInstrument> {
+        self.account_instrument_info()?.instrument()
+    }
+}
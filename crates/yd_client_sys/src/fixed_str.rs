@@ -0,0 +1,51 @@
+use std::{fmt, os::raw::c_char, slice};
+
+/// returned by the setters of fixed-size string fields like `YDInstrument::set_instrument_id`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixedStrError {
+    /// `\0` at `position` would truncate the value in C++
+    InteriorNul { position: usize },
+    /// the value needs `len + 1` bytes with its terminating `\0`, but the field only has `capacity`
+    TooLong { len: usize, capacity: usize },
+}
+
+impl fmt::Display for FixedStrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InteriorNul { position } => write!(f, "string contains NUL at {position}"),
+            Self::TooLong { len, capacity } => {
+                write!(f, "string of {len} bytes does not fit in a field of {capacity} bytes")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FixedStrError {}
+
+/// copy `value` and a terminating `\0` into `buffer`, the rest of `buffer` is zeroed.
+/// `buffer` is not changed if `value` is rejected.
+pub fn set_fixed_str(buffer: &mut [c_char], value: &str) -> Result<(), FixedStrError> {
+    let bytes = value.as_bytes();
+    if let Some(position) = bytes.iter().position(|&byte| byte == 0) {
+        return Err(FixedStrError::InteriorNul { position });
+    }
+    if bytes.len() >= buffer.len() {
+        return Err(FixedStrError::TooLong {
+            len: bytes.len(),
+            capacity: buffer.len(),
+        });
+    }
+    for (target, &byte) in buffer.iter_mut().zip(bytes) {
+        *target = byte as c_char;
+    }
+    buffer[bytes.len()..].fill(0);
+    Ok(())
+}
+
+/// the value of a fixed-size string field before its first `\0`, or the whole `buffer` without `\0`.
+/// IDs are ASCII in practice, it is empty if the value is not valid UTF-8
+pub fn get_fixed_str(buffer: &[c_char]) -> &str {
+    let bytes = unsafe { slice::from_raw_parts(buffer.as_ptr().cast::<u8>(), buffer.len()) };
+    let len = bytes.iter().position(|&byte| byte == 0).unwrap_or(bytes.len());
+    std::str::from_utf8(&bytes[..len]).unwrap_or_default()
+}
@@ -0,0 +1,31 @@
+//! generated by build.rs into `OUT_DIR`
+
+// bindgen 的输出，不由 clippy 检查
+#[allow(clippy::all)]
+pub mod bindings {
+    include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+}
+
+pub mod api_wrapper {
+    include!(concat!(env!("OUT_DIR"), "/api_wrapper.rs"));
+}
+
+pub mod spi_wrapper {
+    include!(concat!(env!("OUT_DIR"), "/spi_wrapper.rs"));
+}
+
+pub mod data_types {
+    include!(concat!(env!("OUT_DIR"), "/data_types.rs"));
+}
+
+mod string_fields {
+    include!(concat!(env!("OUT_DIR"), "/string_fields.rs"));
+}
+
+mod accessors {
+    include!(concat!(env!("OUT_DIR"), "/accessors.rs"));
+}
+
+pub mod yd_error {
+    include!(concat!(env!("OUT_DIR"), "/yd_error.rs"));
+}
@@ -0,0 +1,758 @@
+//! typed builders of `YDInputOrder`, following the field requirements after `class YDInputOrder` in ydDataStruct.h
+
+use std::{fmt, os::raw::c_char};
+
+use crate::{
+    bindings::{YDCombPositionDef, YDExchange, YDInputOrder, YDInputQuote, YDInstrument},
+    data_types::{
+        CombPositionDirection, ConnectionSelectionType, CoverDirection, Direction, FreezeUnderlyingDirection, HedgeFlag,
+        MarkOrderType, OffsetFlag, OptionSelfCloseOrderType, OrderFlag, OrderGroupRefControl, OrderType, ProductClass,
+        StockHedgeFlag,
+    },
+};
+
+/// exchanges listed in the comments of `YD_YOF_XXX` in ydDataType.h, `Normal` is supported everywhere
+const ORDER_FLAG_EXCHANGES: [(OrderFlag, &[&str]); 8] = [
+    (OrderFlag::OptionExecute, &["SHFE", "INE", "DCE", "CZCE", "GFEX"]),
+    (OrderFlag::OptionAbandonExecute, &["SHFE", "INE", "CZCE"]),
+    (OrderFlag::RequestForQuote, &["SHFE", "INE", "CFFEX", "DCE", "CZCE", "GFEX"]),
+    (OrderFlag::CombPosition, &["DCE", "SSE", "SZSE", "GFEX"]),
+    (OrderFlag::Mark, &["DCE", "GFEX"]),
+    (OrderFlag::OptionSelfClose, &["SHFE", "INE"]),
+    (OrderFlag::FreezeUnderlying, &["SSE"]),
+    (OrderFlag::Cover, &["SSE", "SZSE"]),
+];
+
+/// connection ids are indexes of `YDExchange::IsPublicConnectionID`
+const MAX_CONNECTION_ID: u8 = 63;
+/// `OrderGroupID` 0 is normal OrderRef management, 1-63 are strict management groups
+const MAX_ORDER_GROUP_ID: u8 = 63;
+
+/// reasons why a builder rejects the order before it is sent to libyd
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputOrderError {
+    /// the exchange doesn't accept this kind of order, e.g. `Mark` outside DCE and GFEX
+    OrderFlagNotSupported { order_flag: OrderFlag, exchange_id: String },
+    /// `CloseToday` and `CloseYesterday` are only for exchanges using today position (SHFE and INE), which don't accept `Close`.
+    /// `Open1Close2` and `Close1Open2` are only for combination instruments, option execution only closes positions
+    InvalidOffsetFlag { offset_flag: OffsetFlag, exchange_id: String },
+    /// volume must be positive, except `OptionSelfClose` which accepts 0
+    InvalidVolume { volume: i32 },
+    /// price of limit, FAK and FOK orders must be finite
+    InvalidPrice { price: f64 },
+    InvalidConnectionId { connection_id: u8 },
+    InvalidOrderGroupId { order_group_id: u8 },
+    /// `CombPositionDetailID` is only used to split comb positions on SSE and SZSE
+    UnexpectedCombPositionDetailId { exchange_id: String },
+    /// `m_pExchange` of the instrument or comb position def is null, it is not given by libyd
+    MissingExchange,
+}
+
+impl fmt::Display for InputOrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OrderFlagNotSupported { order_flag, exchange_id } => {
+                write!(f, "{order_flag} orders are not supported by {exchange_id}")
+            }
+            Self::InvalidOffsetFlag { offset_flag, exchange_id } => {
+                write!(f, "offset flag {offset_flag} is not valid for this order on {exchange_id}")
+            }
+            Self::InvalidVolume { volume } => write!(f, "invalid order volume {volume}"),
+            Self::InvalidPrice { price } => write!(f, "invalid order price {price}"),
+            Self::InvalidConnectionId { connection_id } => {
+                write!(f, "connection id {connection_id} is out of 0-{MAX_CONNECTION_ID}")
+            }
+            Self::InvalidOrderGroupId { order_group_id } => {
+                write!(f, "order group id {order_group_id} is out of 1-{MAX_ORDER_GROUP_ID}")
+            }
+            Self::UnexpectedCombPositionDetailId { exchange_id } => {
+                write!(f, "comb position detail id is only used to split comb positions on SSE and SZSE, not {exchange_id}")
+            }
+            Self::MissingExchange => write!(f, "exchange of the instrument is null"),
+        }
+    }
+}
+
+impl std::error::Error for InputOrderError {}
+
+/// `HedgeFlag` of orders and quotes, futures exchanges and stock exchanges have their own hedge flags in ydDataType.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderHedgeFlag {
+    Futures(HedgeFlag),
+    /// SSE and SZSE, e.g. `StockHedgeFlag::Covered` for covered orders
+    Stock(StockHedgeFlag),
+}
+
+impl Default for OrderHedgeFlag {
+    fn default() -> Self {
+        Self::Futures(HedgeFlag::Speculation)
+    }
+}
+
+impl From<HedgeFlag> for OrderHedgeFlag {
+    fn from(value: HedgeFlag) -> Self {
+        Self::Futures(value)
+    }
+}
+
+impl From<StockHedgeFlag> for OrderHedgeFlag {
+    fn from(value: StockHedgeFlag) -> Self {
+        Self::Stock(value)
+    }
+}
+
+impl From<OrderHedgeFlag> for c_char {
+    fn from(value: OrderHedgeFlag) -> Self {
+        match value {
+            OrderHedgeFlag::Futures(hedge_flag) => hedge_flag.into(),
+            OrderHedgeFlag::Stock(hedge_flag) => hedge_flag.into(),
+        }
+    }
+}
+
+/// `ConnectionSelectionType` and `ConnectionID`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Connection {
+    #[default]
+    Any,
+    Fixed(u8),
+    Prefered(u8),
+}
+
+/// strict OrderRef management, `OrderGroupID` and `GroupOrderRefControl`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderGroup {
+    /// 1-63
+    pub id: u8,
+    pub ref_control: OrderGroupRefControl,
+}
+
+/// fields shared by all kinds of orders and quotes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OrderOptions {
+    /// user defined reference passed back in order and trade notifications, should be positive.
+    /// 0 is reserved for ydClient and is set by `check_and_insert_order`
+    pub order_ref: i32,
+    pub connection: Connection,
+    /// `None` for OrderGroupID 0, the normal OrderRef management
+    pub order_group: Option<OrderGroup>,
+}
+
+impl Connection {
+    /// `ConnectionSelectionType` and `ConnectionID`
+    pub(crate) fn fields(&self) -> Result<(c_char, c_char), InputOrderError> {
+        let (connection_selection_type, connection_id) = match *self {
+            Connection::Any => (ConnectionSelectionType::Any, 0),
+            Connection::Fixed(connection_id) => (ConnectionSelectionType::Fixed, connection_id),
+            Connection::Prefered(connection_id) => (ConnectionSelectionType::Prefered, connection_id),
+        };
+        if connection_id > MAX_CONNECTION_ID {
+            return Err(InputOrderError::InvalidConnectionId { connection_id });
+        }
+        Ok((connection_selection_type.into(), connection_id as c_char))
+    }
+}
+
+/// `OrderGroupID` must be 1-63 when it is not 0
+pub(crate) fn check_order_group_id(order_group_id: u8) -> Result<(), InputOrderError> {
+    if (1..=MAX_ORDER_GROUP_ID).contains(&order_group_id) {
+        Ok(())
+    } else {
+        Err(InputOrderError::InvalidOrderGroupId { order_group_id })
+    }
+}
+
+impl OrderOptions {
+    fn apply(&self, input_order: &mut YDInputOrder) -> Result<(), InputOrderError> {
+        input_order.OrderRef = self.order_ref;
+        (input_order.ConnectionSelectionType, input_order.ConnectionID) = self.connection.fields()?;
+        if let Some(OrderGroup { id, ref_control }) = self.order_group {
+            check_order_group_id(id)?;
+            input_order.OrderGroupID = id;
+            input_order.GroupOrderRefControl = ref_control.into();
+        }
+        Ok(())
+    }
+
+    /// `YDInputQuote` has the same fields as `YDInputOrder`
+    pub(crate) fn apply_to_quote(&self, input_quote: &mut YDInputQuote) -> Result<(), InputOrderError> {
+        input_quote.OrderRef = self.order_ref;
+        (input_quote.ConnectionSelectionType, input_quote.ConnectionID) = self.connection.fields()?;
+        if let Some(OrderGroup { id, ref_control }) = self.order_group {
+            check_order_group_id(id)?;
+            input_quote.OrderGroupID = id;
+            input_quote.GroupOrderRefControl = ref_control.into();
+        }
+        Ok(())
+    }
+}
+
+/// builds a `YDInputOrder` with all unused fields set to 0, or rejects an invalid combination of fields.
+///
+/// `build` reads the exchange of the instrument through its `m_pExchange`, so the constructors of builders are unsafe,
+/// and the instrument is not a public field. It must be given by libyd, e.g. by `Catalog`, whose `m_pExchange`
+/// is valid until the api is destroyed.
+pub trait InputOrderBuilder {
+    fn build(&self) -> Result<YDInputOrder, InputOrderError>;
+}
+
+pub(crate) fn exchange_id_of(exchange: &YDExchange) -> String {
+    exchange.exchange_id().to_string()
+}
+
+/// # Safety
+///
+/// `m_pExchange` of `instrument` must be null or valid, that is, the instrument is given by libyd, see `InputOrderBuilder`
+unsafe fn exchange_of(instrument: &YDInstrument) -> Result<&YDExchange, InputOrderError> {
+    instrument.exchange().ok_or(InputOrderError::MissingExchange)
+}
+
+fn check_order_flag(order_flag: OrderFlag, exchange: &YDExchange) -> Result<(), InputOrderError> {
+    let supported = match ORDER_FLAG_EXCHANGES.iter().find(|(flag, _)| *flag == order_flag) {
+        Some((_, exchanges)) => exchanges.contains(&exchange.exchange_id()),
+        None => true,
+    };
+    if supported {
+        Ok(())
+    } else {
+        Err(InputOrderError::OrderFlagNotSupported {
+            order_flag,
+            exchange_id: exchange_id_of(exchange),
+        })
+    }
+}
+
+/// "For SHFE and INE, only YD_OF_CloseYesterday and YD_OF_CloseToday are valid for close" in ydDataType.h,
+/// they are the exchanges with `UseTodayPosition`
+fn check_close_offset_flag(offset_flag: OffsetFlag, exchange: &YDExchange) -> Result<(), InputOrderError> {
+    let valid = match offset_flag {
+        OffsetFlag::Close => !exchange.UseTodayPosition,
+        OffsetFlag::CloseToday | OffsetFlag::CloseYesterday => exchange.UseTodayPosition,
+        _ => true,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(InputOrderError::InvalidOffsetFlag {
+            offset_flag,
+            exchange_id: exchange_id_of(exchange),
+        })
+    }
+}
+
+/// `Open1Close2` and `Close1Open2` are only for combination instruments
+///
+/// # Safety
+///
+/// same as `exchange_of`
+pub(crate) unsafe fn check_offset_flag(offset_flag: OffsetFlag, instrument: &YDInstrument) -> Result<(), InputOrderError> {
+    let exchange = exchange_of(instrument)?;
+    if matches!(offset_flag, OffsetFlag::Open1Close2 | OffsetFlag::Close1Open2)
+        && instrument.product_class() != Ok(ProductClass::Combination)
+    {
+        return Err(InputOrderError::InvalidOffsetFlag {
+            offset_flag,
+            exchange_id: exchange_id_of(exchange),
+        });
+    }
+    check_close_offset_flag(offset_flag, exchange)
+}
+
+pub(crate) fn check_volume(volume: i32) -> Result<(), InputOrderError> {
+    if volume > 0 {
+        Ok(())
+    } else {
+        Err(InputOrderError::InvalidVolume { volume })
+    }
+}
+
+/// `OrderFlag::Normal` orders, the price is ignored by market orders
+///
+/// # Safety
+///
+/// same as `exchange_of`
+#[allow(clippy::too_many_arguments)]
+unsafe fn build_normal_order(
+    instrument: &YDInstrument,
+    order_type: OrderType,
+    direction: Direction,
+    offset_flag: OffsetFlag,
+    hedge_flag: OrderHedgeFlag,
+    price: f64,
+    volume: i32,
+    options: &OrderOptions,
+) -> Result<YDInputOrder, InputOrderError> {
+    check_volume(volume)?;
+    if order_type != OrderType::Market && !price.is_finite() {
+        return Err(InputOrderError::InvalidPrice { price });
+    }
+    check_offset_flag(offset_flag, instrument)?;
+    let mut input_order = YDInputOrder {
+        Direction: direction.into(),
+        OffsetFlag: offset_flag.into(),
+        HedgeFlag: hedge_flag.into(),
+        OrderVolume: volume,
+        OrderType: order_type.into(),
+        YDOrderFlag: OrderFlag::Normal.into(),
+        ..Default::default()
+    };
+    if order_type != OrderType::Market {
+        input_order.__bindgen_anon_1.Price = price;
+    }
+    options.apply(&mut input_order)?;
+    Ok(input_order)
+}
+
+/// orders of `OrderFlag` other than `Normal`, whose `HedgeFlag` is meaningless but must be set
+fn build_flagged_order(
+    exchange: &YDExchange,
+    order_flag: OrderFlag,
+    direction: c_char,
+    order_type: c_char,
+    volume: i32,
+    options: &OrderOptions,
+) -> Result<YDInputOrder, InputOrderError> {
+    check_order_flag(order_flag, exchange)?;
+    let mut input_order = YDInputOrder {
+        Direction: direction,
+        HedgeFlag: HedgeFlag::Speculation.into(),
+        OrderVolume: volume,
+        OrderType: order_type,
+        YDOrderFlag: order_flag.into(),
+        ..Default::default()
+    };
+    options.apply(&mut input_order)?;
+    Ok(input_order)
+}
+
+macro_rules! priced_order {
+    ($(#[$doc: meta])* $name: ident, $order_type: expr) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy)]
+        pub struct $name<'a> {
+            instrument: &'a YDInstrument,
+            pub direction: Direction,
+            pub offset_flag: OffsetFlag,
+            /// `HedgeFlag::Speculation` by default, e.g. `StockHedgeFlag::Covered.into()` for covered orders of SSE and SZSE
+            pub hedge_flag: OrderHedgeFlag,
+            pub price: f64,
+            pub volume: i32,
+            pub options: OrderOptions,
+        }
+
+        impl<'a> $name<'a> {
+            /// # Safety
+            ///
+            /// `instrument` must be given by libyd, see `InputOrderBuilder`
+            pub unsafe fn new(instrument: &'a YDInstrument, direction: Direction, offset_flag: OffsetFlag, price: f64, volume: i32) -> Self {
+                Self {
+                    instrument,
+                    direction,
+                    offset_flag,
+                    hedge_flag: OrderHedgeFlag::default(),
+                    price,
+                    volume,
+                    options: OrderOptions::default(),
+                }
+            }
+        }
+
+        impl InputOrderBuilder for $name<'_> {
+            fn build(&self) -> Result<YDInputOrder, InputOrderError> {
+                // `new` 要求 instrument 由 libyd 给出
+                unsafe {
+                    build_normal_order(
+                        self.instrument,
+                        $order_type,
+                        self.direction,
+                        self.offset_flag,
+                        self.hedge_flag,
+                        self.price,
+                        self.volume,
+                        &self.options,
+                    )
+                }
+            }
+        }
+    };
+}
+
+priced_order!(
+    /// `YD_ODT_Limit`
+    LimitOrder,
+    OrderType::Limit
+);
+priced_order!(
+    /// `YD_ODT_FAK`, fill and kill
+    FakOrder,
+    OrderType::FAK
+);
+priced_order!(
+    /// `YD_ODT_FOK`, fill or kill
+    FokOrder,
+    OrderType::FOK
+);
+
+/// `YD_ODT_Market`
+#[derive(Debug, Clone, Copy)]
+pub struct MarketOrder<'a> {
+    instrument: &'a YDInstrument,
+    pub direction: Direction,
+    pub offset_flag: OffsetFlag,
+    pub hedge_flag: OrderHedgeFlag,
+    pub volume: i32,
+    pub options: OrderOptions,
+}
+
+impl<'a> MarketOrder<'a> {
+    /// # Safety
+    ///
+    /// `instrument` must be given by libyd, see `InputOrderBuilder`
+    pub unsafe fn new(instrument: &'a YDInstrument, direction: Direction, offset_flag: OffsetFlag, volume: i32) -> Self {
+        Self {
+            instrument,
+            direction,
+            offset_flag,
+            hedge_flag: OrderHedgeFlag::default(),
+            volume,
+            options: OrderOptions::default(),
+        }
+    }
+}
+
+impl InputOrderBuilder for MarketOrder<'_> {
+    fn build(&self) -> Result<YDInputOrder, InputOrderError> {
+        unsafe {
+            build_normal_order(
+                self.instrument,
+                OrderType::Market,
+                self.direction,
+                self.offset_flag,
+                self.hedge_flag,
+                0.0,
+                self.volume,
+                &self.options,
+            )
+        }
+    }
+}
+
+/// `YD_YOF_OptionExecute` or `YD_YOF_OptionAbandonExecute`, `offset_flag` specifies which position to execute
+#[derive(Debug, Clone, Copy)]
+pub struct OptionExecute<'a> {
+    instrument: &'a YDInstrument,
+    pub offset_flag: OffsetFlag,
+    pub hedge_flag: OrderHedgeFlag,
+    pub volume: i32,
+    /// `YD_YOF_OptionAbandonExecute`
+    pub abandon: bool,
+    pub options: OrderOptions,
+}
+
+impl<'a> OptionExecute<'a> {
+    /// # Safety
+    ///
+    /// `instrument` must be given by libyd, see `InputOrderBuilder`
+    pub unsafe fn execute(instrument: &'a YDInstrument, offset_flag: OffsetFlag, volume: i32) -> Self {
+        Self {
+            instrument,
+            offset_flag,
+            hedge_flag: OrderHedgeFlag::default(),
+            volume,
+            abandon: false,
+            options: OrderOptions::default(),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `instrument` must be given by libyd, see `InputOrderBuilder`
+    pub unsafe fn abandon(instrument: &'a YDInstrument, offset_flag: OffsetFlag, volume: i32) -> Self {
+        Self {
+            abandon: true,
+            ..Self::execute(instrument, offset_flag, volume)
+        }
+    }
+}
+
+impl InputOrderBuilder for OptionExecute<'_> {
+    fn build(&self) -> Result<YDInputOrder, InputOrderError> {
+        let exchange = unsafe { exchange_of(self.instrument) }?;
+        let order_flag = if self.abandon {
+            OrderFlag::OptionAbandonExecute
+        } else {
+            OrderFlag::OptionExecute
+        };
+        check_volume(self.volume)?;
+        if !matches!(
+            self.offset_flag,
+            OffsetFlag::Close | OffsetFlag::CloseToday | OffsetFlag::CloseYesterday
+        ) {
+            return Err(InputOrderError::InvalidOffsetFlag {
+                offset_flag: self.offset_flag,
+                exchange_id: exchange_id_of(exchange),
+            });
+        }
+        check_close_offset_flag(self.offset_flag, exchange)?;
+        // Direction 和 OrderType 由系统设置，保持为 0
+        let mut input_order = build_flagged_order(exchange, order_flag, 0, 0, self.volume, &self.options)?;
+        input_order.OffsetFlag = self.offset_flag.into();
+        input_order.HedgeFlag = self.hedge_flag.into();
+        Ok(input_order)
+    }
+}
+
+/// `YD_YOF_RequestForQuote`, there is no notification for it
+#[derive(Debug, Clone, Copy)]
+pub struct RequestForQuote<'a> {
+    instrument: &'a YDInstrument,
+    pub options: OrderOptions,
+}
+
+impl<'a> RequestForQuote<'a> {
+    /// # Safety
+    ///
+    /// `instrument` must be given by libyd, see `InputOrderBuilder`
+    pub unsafe fn new(instrument: &'a YDInstrument) -> Self {
+        Self {
+            instrument,
+            options: OrderOptions::default(),
+        }
+    }
+}
+
+impl InputOrderBuilder for RequestForQuote<'_> {
+    fn build(&self) -> Result<YDInputOrder, InputOrderError> {
+        build_flagged_order(unsafe { exchange_of(self.instrument) }?, OrderFlag::RequestForQuote, 0, 0, 0, &self.options)
+    }
+}
+
+/// `YD_YOF_CombPosition`, sent by `insert_comb_position_order` with `comb_position_def` instead of an instrument
+#[derive(Debug, Clone, Copy)]
+pub struct CombPositionOrder<'a> {
+    comb_position_def: &'a YDCombPositionDef,
+    pub direction: CombPositionDirection,
+    pub volume: i32,
+    /// only used to split comb positions on SSE and SZSE
+    pub comb_position_detail_id: Option<i32>,
+    pub options: OrderOptions,
+}
+
+impl<'a> CombPositionOrder<'a> {
+    /// # Safety
+    ///
+    /// `comb_position_def` must be given by libyd, e.g. by `Catalog::comb_position_def`, see `InputOrderBuilder`
+    pub unsafe fn make(comb_position_def: &'a YDCombPositionDef, volume: i32) -> Self {
+        Self {
+            comb_position_def,
+            direction: CombPositionDirection::Make,
+            volume,
+            comb_position_detail_id: None,
+            options: OrderOptions::default(),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `comb_position_def` must be given by libyd, e.g. by `Catalog::comb_position_def`, see `InputOrderBuilder`
+    pub unsafe fn split(comb_position_def: &'a YDCombPositionDef, volume: i32) -> Self {
+        Self {
+            direction: CombPositionDirection::Split,
+            ..Self::make(comb_position_def, volume)
+        }
+    }
+
+    /// split a comb position detail on SSE or SZSE
+    ///
+    /// # Safety
+    ///
+    /// same as `split`
+    pub unsafe fn split_detail(comb_position_def: &'a YDCombPositionDef, comb_position_detail_id: i32, volume: i32) -> Self {
+        Self {
+            comb_position_detail_id: Some(comb_position_detail_id),
+            ..Self::split(comb_position_def, volume)
+        }
+    }
+}
+
+impl InputOrderBuilder for CombPositionOrder<'_> {
+    fn build(&self) -> Result<YDInputOrder, InputOrderError> {
+        // `make` 和 `split` 要求 comb position def 由 libyd 给出
+        let exchange = unsafe { self.comb_position_def.exchange() }.ok_or(InputOrderError::MissingExchange)?;
+        check_volume(self.volume)?;
+        let mut input_order =
+            build_flagged_order(exchange, OrderFlag::CombPosition, self.direction.into(), 0, self.volume, &self.options)?;
+        if let Some(comb_position_detail_id) = self.comb_position_detail_id {
+            if self.direction != CombPositionDirection::Split || !["SSE", "SZSE"].contains(&exchange.exchange_id()) {
+                return Err(InputOrderError::UnexpectedCombPositionDetailId {
+                    exchange_id: exchange_id_of(exchange),
+                });
+            }
+            // CombPositionDetailID 和 Price 在同一个 union 中
+            input_order.__bindgen_anon_1.__bindgen_anon_1.CombPositionDetailID = comb_position_detail_id;
+        }
+        Ok(input_order)
+    }
+}
+
+/// `YD_YOF_Mark`. For `CloseFuturesPositionMark`, `instrument` only means the related exchange
+#[derive(Debug, Clone, Copy)]
+pub struct Mark<'a> {
+    instrument: &'a YDInstrument,
+    pub order_type: MarkOrderType,
+    /// `false` to cancel the mark
+    pub set: bool,
+    pub options: OrderOptions,
+}
+
+impl<'a> Mark<'a> {
+    /// # Safety
+    ///
+    /// `instrument` must be given by libyd, see `InputOrderBuilder`
+    pub unsafe fn set(instrument: &'a YDInstrument, order_type: MarkOrderType) -> Self {
+        Self {
+            instrument,
+            order_type,
+            set: true,
+            options: OrderOptions::default(),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `instrument` must be given by libyd, see `InputOrderBuilder`
+    pub unsafe fn cancel(instrument: &'a YDInstrument, order_type: MarkOrderType) -> Self {
+        Self {
+            set: false,
+            ..Self::set(instrument, order_type)
+        }
+    }
+}
+
+impl InputOrderBuilder for Mark<'_> {
+    fn build(&self) -> Result<YDInputOrder, InputOrderError> {
+        // YD_D_Buy 设置标记，YD_D_Sell 取消标记
+        let direction = if self.set { Direction::Buy } else { Direction::Sell };
+        build_flagged_order(
+            unsafe { exchange_of(self.instrument) }?,
+            OrderFlag::Mark,
+            direction.into(),
+            self.order_type.into(),
+            0,
+            &self.options,
+        )
+    }
+}
+
+/// `YD_YOF_OptionSelfClose`, `volume` can be 0
+#[derive(Debug, Clone, Copy)]
+pub struct OptionSelfClose<'a> {
+    instrument: &'a YDInstrument,
+    pub order_type: OptionSelfCloseOrderType,
+    pub volume: i32,
+    pub options: OrderOptions,
+}
+
+impl<'a> OptionSelfClose<'a> {
+    /// # Safety
+    ///
+    /// `instrument` must be given by libyd, see `InputOrderBuilder`
+    pub unsafe fn new(instrument: &'a YDInstrument, order_type: OptionSelfCloseOrderType, volume: i32) -> Self {
+        Self {
+            instrument,
+            order_type,
+            volume,
+            options: OrderOptions::default(),
+        }
+    }
+}
+
+impl InputOrderBuilder for OptionSelfClose<'_> {
+    fn build(&self) -> Result<YDInputOrder, InputOrderError> {
+        if self.volume < 0 {
+            return Err(InputOrderError::InvalidVolume { volume: self.volume });
+        }
+        build_flagged_order(
+            unsafe { exchange_of(self.instrument) }?,
+            OrderFlag::OptionSelfClose,
+            0,
+            self.order_type.into(),
+            self.volume,
+            &self.options,
+        )
+    }
+}
+
+/// `YD_YOF_FreezeUnderlying`
+#[derive(Debug, Clone, Copy)]
+pub struct FreezeUnderlying<'a> {
+    instrument: &'a YDInstrument,
+    pub direction: FreezeUnderlyingDirection,
+    pub volume: i32,
+    pub options: OrderOptions,
+}
+
+impl<'a> FreezeUnderlying<'a> {
+    /// # Safety
+    ///
+    /// `instrument` must be given by libyd, see `InputOrderBuilder`
+    pub unsafe fn new(instrument: &'a YDInstrument, direction: FreezeUnderlyingDirection, volume: i32) -> Self {
+        Self {
+            instrument,
+            direction,
+            volume,
+            options: OrderOptions::default(),
+        }
+    }
+}
+
+impl InputOrderBuilder for FreezeUnderlying<'_> {
+    fn build(&self) -> Result<YDInputOrder, InputOrderError> {
+        check_volume(self.volume)?;
+        build_flagged_order(
+            unsafe { exchange_of(self.instrument) }?,
+            OrderFlag::FreezeUnderlying,
+            self.direction.into(),
+            0,
+            self.volume,
+            &self.options,
+        )
+    }
+}
+
+/// `YD_YOF_Cover`, conversion between normal and covered positions, not a covered order
+#[derive(Debug, Clone, Copy)]
+pub struct Cover<'a> {
+    instrument: &'a YDInstrument,
+    pub direction: CoverDirection,
+    pub volume: i32,
+    pub options: OrderOptions,
+}
+
+impl<'a> Cover<'a> {
+    /// # Safety
+    ///
+    /// `instrument` must be given by libyd, see `InputOrderBuilder`
+    pub unsafe fn new(instrument: &'a YDInstrument, direction: CoverDirection, volume: i32) -> Self {
+        Self {
+            instrument,
+            direction,
+            volume,
+            options: OrderOptions::default(),
+        }
+    }
+}
+
+impl InputOrderBuilder for Cover<'_> {
+    fn build(&self) -> Result<YDInputOrder, InputOrderError> {
+        check_volume(self.volume)?;
+        build_flagged_order(
+            unsafe { exchange_of(self.instrument) }?,
+            OrderFlag::Cover,
+            self.direction.into(),
+            0,
+            self.volume,
+            &self.options,
+        )
+    }
+}
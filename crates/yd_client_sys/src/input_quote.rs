@@ -0,0 +1,138 @@
+//! typed builder of `YDInputQuote` and constructors of `YDCancelOrder` and `YDCancelQuote`
+
+use std::os::raw::c_char;
+
+use crate::{
+    bindings::{YDCancelOrder, YDCancelQuote, YDInputQuote, YDInstrument},
+    data_types::{OffsetFlag, QuoteFlag},
+    input_order::{
+        check_offset_flag, check_order_group_id, check_volume, Connection, InputOrderError, OrderHedgeFlag, OrderOptions,
+    },
+};
+
+/// one side of a quote
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuoteSide {
+    pub price: f64,
+    pub volume: i32,
+    pub offset_flag: OffsetFlag,
+    /// `HedgeFlag::Speculation` by default
+    pub hedge_flag: OrderHedgeFlag,
+}
+
+impl QuoteSide {
+    pub fn new(price: f64, volume: i32, offset_flag: OffsetFlag) -> Self {
+        Self {
+            price,
+            volume,
+            offset_flag,
+            hedge_flag: OrderHedgeFlag::default(),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `instrument` must be given by libyd, see `InputQuote::new`
+    unsafe fn check(&self, instrument: &YDInstrument) -> Result<(), InputOrderError> {
+        check_volume(self.volume)?;
+        if !self.price.is_finite() {
+            return Err(InputOrderError::InvalidPrice { price: self.price });
+        }
+        check_offset_flag(self.offset_flag, instrument)
+    }
+}
+
+/// `YDInputQuote`, sent by `insert_quote`
+#[derive(Debug, Clone, Copy)]
+pub struct InputQuote<'a> {
+    instrument: &'a YDInstrument,
+    pub bid: QuoteSide,
+    pub ask: QuoteSide,
+    /// `ResponseOfRFQ` and `ReplaceLastQuote`, empty by default
+    pub quote_flag: QuoteFlag,
+    pub options: OrderOptions,
+}
+
+impl<'a> InputQuote<'a> {
+    /// # Safety
+    ///
+    /// `instrument` must be given by libyd, e.g. by `Catalog`, `build` reads its `m_pExchange`, see `InputOrderBuilder`
+    pub unsafe fn new(instrument: &'a YDInstrument, bid: QuoteSide, ask: QuoteSide) -> Self {
+        Self {
+            instrument,
+            bid,
+            ask,
+            quote_flag: QuoteFlag::empty(),
+            options: OrderOptions::default(),
+        }
+    }
+
+    /// builds a `YDInputQuote` with all unused fields set to 0, or rejects an invalid side
+    pub fn build(&self) -> Result<YDInputQuote, InputOrderError> {
+        unsafe {
+            self.bid.check(self.instrument)?;
+            self.ask.check(self.instrument)?;
+        }
+        let mut input_quote = YDInputQuote {
+            BidOffsetFlag: self.bid.offset_flag.into(),
+            BidHedgeFlag: self.bid.hedge_flag.into(),
+            AskOffsetFlag: self.ask.offset_flag.into(),
+            AskHedgeFlag: self.ask.hedge_flag.into(),
+            BidPrice: self.bid.price,
+            AskPrice: self.ask.price,
+            BidVolume: self.bid.volume,
+            AskVolume: self.ask.volume,
+            YDQuoteFlag: self.quote_flag.bits() as c_char,
+            ..Default::default()
+        };
+        self.options.apply_to_quote(&mut input_quote)?;
+        Ok(input_quote)
+    }
+}
+
+/// the order or quote to cancel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelTarget {
+    /// `OrderSysID` or `QuoteSysID`
+    SysId(i32),
+    /// `LongOrderSysID` or `LongQuoteSysID`
+    LongSysId(i64),
+    /// `OrderRef` in a strict management group, `OrderGroupID` is 1-63
+    GroupRef { group_id: u8, order_ref: i32 },
+}
+
+impl YDCancelOrder {
+    /// sets the member of the `OrderSysID`/`OrderRef` union selected by `target`, and `OrderGroupID` for `GroupRef`
+    pub fn new(target: CancelTarget, connection: Connection) -> Result<Self, InputOrderError> {
+        let mut cancel_order = Self::default();
+        (cancel_order.ConnectionSelectionType, cancel_order.ConnectionID) = connection.fields()?;
+        match target {
+            CancelTarget::SysId(order_sys_id) => cancel_order.__bindgen_anon_1.OrderSysID = order_sys_id,
+            CancelTarget::LongSysId(long_order_sys_id) => cancel_order.LongOrderSysID = long_order_sys_id,
+            CancelTarget::GroupRef { group_id, order_ref } => {
+                check_order_group_id(group_id)?;
+                cancel_order.OrderGroupID = group_id;
+                cancel_order.__bindgen_anon_1.OrderRef = order_ref;
+            }
+        }
+        Ok(cancel_order)
+    }
+}
+
+impl YDCancelQuote {
+    /// sets the member of the `QuoteSysID`/`OrderRef` union selected by `target`, and `OrderGroupID` for `GroupRef`
+    pub fn new(target: CancelTarget, connection: Connection) -> Result<Self, InputOrderError> {
+        let mut cancel_quote = Self::default();
+        (cancel_quote.ConnectionSelectionType, cancel_quote.ConnectionID) = connection.fields()?;
+        match target {
+            CancelTarget::SysId(quote_sys_id) => cancel_quote.__bindgen_anon_1.QuoteSysID = quote_sys_id,
+            CancelTarget::LongSysId(long_quote_sys_id) => cancel_quote.LongQuoteSysID = long_quote_sys_id,
+            CancelTarget::GroupRef { group_id, order_ref } => {
+                check_order_group_id(group_id)?;
+                cancel_quote.OrderGroupID = group_id;
+                cancel_quote.__bindgen_anon_1.OrderRef = order_ref;
+            }
+        }
+        Ok(cancel_quote)
+    }
+}
@@ -5,44 +5,296 @@
 #![allow(unused_variables, unused_mut)]
 #![allow(clippy::explicit_auto_deref)]
 
+// api_wrapper 和 spi_wrapper 的 v-table 按 Itanium C++ ABI 生成，MSVC 把同名的虚函数重载（如 `getOrder`、`notifyResponse`）
+// 按声明的逆序排在一起，v-table 中的位置不同，调用会进入错误的函数
+#[cfg(not(target_os = "linux"))]
+compile_error!("yd_client_sys only supports linux, v-tables are generated in the Itanium C++ ABI order, not the MSVC one");
+
 mod generated;
 pub use generated::api_wrapper;
 pub use generated::bindings;
-use generated::bindings::{getYDVersion, makeYDApi, YDApi};
+pub use generated::data_types;
+pub use generated::yd_error;
+pub use generated::yd_error::{ErrorExchange, YdError};
+#[cfg(not(feature = "dlopen"))]
+use generated::bindings::{getYDVersion, makeYDApi, makeYDExtendedApi};
+use generated::bindings::{YDApi, YDExtendedApi};
 pub use generated::spi_wrapper;
 
+mod api_handle;
+pub use api_handle::{ApiDestroyPhase, ApiDestroyedError, ApiMut, ApiRecord, YDApiHandle};
+mod catalog;
+pub use catalog::{Catalog, CatalogIter, CatalogNotReady};
+mod create_error;
+pub use create_error::CreateApiError;
+mod data_struct;
+// 从 ctp-sys 原样复制，不修改它来满足新版本的 lint
+#[allow(unknown_lints, mismatched_lifetime_syntaxes, clippy::all)]
 mod ffi_utils;
 pub use ffi_utils::*;
+mod fixed_str;
+pub use fixed_str::{get_fixed_str, set_fixed_str, FixedStrError};
+mod input_order;
+pub use input_order::{
+    CombPositionOrder, Connection, Cover, FakOrder, FokOrder, FreezeUnderlying, InputOrderBuilder, InputOrderError, LimitOrder,
+    Mark, MarketOrder, OptionExecute, OptionSelfClose, OrderGroup, OrderHedgeFlag, OrderOptions, RequestForQuote,
+};
+mod input_quote;
+pub use input_quote::{CancelTarget, InputQuote, QuoteSide};
+mod multi_request;
+pub use multi_request::MultiRequestError;
+mod pre_trade;
+pub use pre_trade::{PreTradeCheck, PreTradeError, TradingUsage};
+mod query_result;
+pub use query_result::{QueryResult, QueryResultIter, RawQueryResult};
+mod spi_queue;
+pub use spi_queue::{OverflowPolicy, SpiQueueConfig, SpiQueueMetrics};
+mod spsc_ring;
+pub use spsc_ring::{RingConsumer, RingOverflowPolicy, RingProducer, SpiRingConfig};
+#[cfg(feature = "dlopen")]
+mod yd_library;
+#[cfg(feature = "dlopen")]
+pub use yd_library::{LoadLibraryError, YdLibrary};
+use generated::spi_wrapper::create_extended_spi;
+use generated::spi_wrapper::create_extended_spi_with_ring;
 use generated::spi_wrapper::create_spi;
+use generated::spi_wrapper::create_spi_with_ring;
+use generated::spi_wrapper::YDExtendedListenerRingConsumer;
+use generated::spi_wrapper::YDExtendedListenerStream;
+use generated::spi_wrapper::YDListenerRingConsumer;
 use generated::spi_wrapper::YDListenerStream;
 use generated::spi_wrapper::YDListenerTrait;
-use std::ffi::{CStr, CString};
+use std::{
+    borrow::Cow,
+    ffi::{c_char, CStr, CString},
+    fs::File,
+    io,
+    path::Path,
+};
 
-pub fn create_yd_api(config_filename: &str) -> Box<YDApi> {
-    let cstr_config = CString::new(config_filename).unwrap();
+/// version of the ydClient SDK selected at build time by `YD_SDK_DIR` or the `sdk-*` features
+pub const YD_SDK_VERSION: &str = env!("YD_CLIENT_SYS_SDK_VERSION");
 
-    // Call the unsafe function to create an instance of YDApi
-    let api_ptr = unsafe { makeYDApi(cstr_config.as_ptr()) };
+/// the generated bindings only match the headers of `YD_SDK_VERSION`, calling another libyd is undefined behavior
+#[cfg(not(feature = "dlopen"))]
+pub fn check_yd_version() -> Result<(), CreateApiError> {
+    check_version(version_from_ptr(unsafe { getYDVersion() }))
+}
 
-    // Ensure that api_ptr is not null
-    if api_ptr.is_null() {
-        panic!("Failed to create YDApi instance, get null pointer.");
+fn check_version(actual: String) -> Result<(), CreateApiError> {
+    if actual == YD_SDK_VERSION {
+        Ok(())
+    } else {
+        Err(CreateApiError::VersionMismatch {
+            expected: YD_SDK_VERSION,
+            actual,
+        })
     }
+}
 
-    // Dereference the raw pointer to get YDApi and encapsulate it in the safe wrapper
-    // Assuming YDApi's constructor or a conversion method is available to encapsulate the raw pointer
-    unsafe { YDApi::from_raw(api_ptr) }
+/// string returned by `getYDVersion`, empty for null
+fn version_from_ptr(version: *const c_char) -> String {
+    unsafe { gb18030_ptr_to_str(version) }.map(Cow::into_owned).unwrap_or_default()
+}
+
+/// `const char *` returned by libyd, used by the generated api methods, `None` for null
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL-terminated string that lives for `'a`
+pub(crate) unsafe fn gb18030_ptr_to_str<'a>(ptr: *const c_char) -> Option<Cow<'a, str>> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(gb18030_cstr_to_str(CStr::from_ptr(ptr).to_bytes()))
+    }
 }
 
-pub fn create_yd_api_and_spi(config_filename: &str) -> (Box<YDApi>, Box<YDListenerStream<'static>>) {
-    let mut api = create_yd_api(config_filename);
+/// check the config file before passing it to C++, which only reports failure by returning null
+fn make_config_cstring(config_filename: &str) -> Result<CString, CreateApiError> {
+    let cstr_config = CString::new(config_filename)?;
+    let path = Path::new(config_filename);
+    let unreadable = |source: io::Error| CreateApiError::ConfigUnreadable {
+        path: path.to_path_buf(),
+        source,
+    };
+    let file = File::open(path).map_err(|error| match error.kind() {
+        io::ErrorKind::NotFound => CreateApiError::ConfigNotFound(path.to_path_buf()),
+        _ => unreadable(error),
+    })?;
+    if !file.metadata().map_err(unreadable)?.is_file() {
+        return Err(unreadable(io::Error::new(io::ErrorKind::InvalidInput, "not a file")));
+    }
+    Ok(cstr_config)
+}
+
+/// api and streams of both listeners, returned by `try_create_yd_extended_api_and_spi`
+pub type YDExtendedApiAndSpi = (
+    YDApiHandle<YDExtendedApi>,
+    Box<YDListenerStream>,
+    Box<YDExtendedListenerStream>,
+);
+
+/// api and ring consumers of both listeners, returned by `try_create_yd_extended_api_with_ring`
+pub type YDExtendedApiAndRing = (
+    YDApiHandle<YDExtendedApi>,
+    YDListenerRingConsumer,
+    YDExtendedListenerRingConsumer,
+);
+
+/// call `makeYDApi` or `makeYDExtendedApi`, linked or loaded by `YdLibrary`
+fn create_api<A: ApiRecord>(
+    make_api: unsafe extern "C" fn(*const c_char) -> *mut A,
+    config_filename: &str,
+) -> Result<YDApiHandle<A>, CreateApiError> {
+    let cstr_config = make_config_cstring(config_filename)?;
+
+    // Call the unsafe function to create an instance of YDApi
+    let api_ptr = unsafe { make_api(cstr_config.as_ptr()) };
+
+    // The handle never deletes the api, it is freed by libyd after startDestroy
+    unsafe { YDApiHandle::from_raw(api_ptr) }.ok_or(CreateApiError::NullApi)
+}
 
+fn start_spi(mut api: YDApiHandle<YDApi>) -> Result<(YDApiHandle<YDApi>, Box<YDListenerStream>), CreateApiError> {
     // Initialize the SPI and get the stream
-    let (spi_stream, spi_ptr) = create_spi();
+    let (spi_stream, spi) = create_spi();
 
     // Register the SPI with the API
-    api.start(spi_ptr as *const dyn YDListenerTrait);
+    if api.start(spi) != Ok(true) {
+        return Err(CreateApiError::StartFailed);
+    }
+
+    Ok((api, spi_stream))
+}
+
+fn start_ring(
+    mut api: YDApiHandle<YDApi>,
+    ring_config: SpiRingConfig,
+) -> Result<(YDApiHandle<YDApi>, YDListenerRingConsumer), CreateApiError> {
+    let (consumer, spi) = create_spi_with_ring(ring_config);
+    if api.start(spi) != Ok(true) {
+        return Err(CreateApiError::StartFailed);
+    }
+    Ok((api, consumer))
+}
+
+fn start_listener<L>(mut api: YDApiHandle<YDApi>, listener: Box<L>) -> Result<YDApiHandle<YDApi>, CreateApiError>
+where
+    L: for<'a> YDListenerTrait<'a> + Send + 'static,
+{
+    if api.start_with_listener(listener) != Ok(true) {
+        return Err(CreateApiError::StartFailed);
+    }
+    Ok(api)
+}
+
+fn start_extended_spi(
+    mut api: YDApiHandle<YDExtendedApi>,
+) -> Result<YDExtendedApiAndSpi, CreateApiError> {
+    let (spi_stream, spi) = create_spi();
+    let (extended_spi_stream, extended_spi) = create_extended_spi();
 
-    (api, spi_stream)
+    // Register both listeners, notifyExtendedXXX are only sent to listeners registered by startExtended
+    if api.start_extended(spi, extended_spi) != Ok(true) {
+        return Err(CreateApiError::StartFailed);
+    }
+
+    Ok((api, spi_stream, extended_spi_stream))
+}
+
+/// both listeners get a ring of `ring_config`
+fn start_extended_ring(
+    mut api: YDApiHandle<YDExtendedApi>,
+    ring_config: SpiRingConfig,
+) -> Result<YDExtendedApiAndRing, CreateApiError> {
+    let (consumer, spi) = create_spi_with_ring(ring_config);
+    let (extended_consumer, extended_spi) = create_extended_spi_with_ring(ring_config);
+    if api.start_extended(spi, extended_spi) != Ok(true) {
+        return Err(CreateApiError::StartFailed);
+    }
+    Ok((api, consumer, extended_consumer))
+}
+
+#[cfg(not(feature = "dlopen"))]
+pub fn try_create_yd_api(config_filename: &str) -> Result<YDApiHandle<YDApi>, CreateApiError> {
+    check_yd_version()?;
+    create_api(makeYDApi, config_filename)
+}
+
+#[cfg(not(feature = "dlopen"))]
+pub fn create_yd_api(config_filename: &str) -> YDApiHandle<YDApi> {
+    try_create_yd_api(config_filename).unwrap_or_else(|error| panic!("{error}"))
+}
+
+#[cfg(not(feature = "dlopen"))]
+pub fn try_create_yd_api_and_spi(
+    config_filename: &str,
+) -> Result<(YDApiHandle<YDApi>, Box<YDListenerStream>), CreateApiError> {
+    start_spi(try_create_yd_api(config_filename)?)
+}
+
+#[cfg(not(feature = "dlopen"))]
+pub fn create_yd_api_and_spi(config_filename: &str) -> (YDApiHandle<YDApi>, Box<YDListenerStream>) {
+    try_create_yd_api_and_spi(config_filename).unwrap_or_else(|error| panic!("{error}"))
 }
 
+/// events are pushed to a lock-free ring instead of the async stream, for consumers busy-polling from a pinned thread
+#[cfg(not(feature = "dlopen"))]
+pub fn try_create_yd_api_with_ring(
+    config_filename: &str,
+    ring_config: SpiRingConfig,
+) -> Result<(YDApiHandle<YDApi>, YDListenerRingConsumer), CreateApiError> {
+    start_ring(try_create_yd_api(config_filename)?, ring_config)
+}
+
+/// register a listener implemented by the caller instead of the generated stream, see `YDApiHandle::start_with_listener`
+#[cfg(not(feature = "dlopen"))]
+pub fn try_create_yd_api_with_listener<L>(
+    config_filename: &str,
+    listener: Box<L>,
+) -> Result<YDApiHandle<YDApi>, CreateApiError>
+where
+    L: for<'a> YDListenerTrait<'a> + Send + 'static,
+{
+    start_listener(try_create_yd_api(config_filename)?, listener)
+}
+
+/// YDExtendedApi keeps local copies of orders, trades and positions, and can do local validation
+#[cfg(not(feature = "dlopen"))]
+pub fn try_create_yd_extended_api(config_filename: &str) -> Result<YDApiHandle<YDExtendedApi>, CreateApiError> {
+    check_yd_version()?;
+    create_api(makeYDExtendedApi, config_filename)
+}
+
+#[cfg(not(feature = "dlopen"))]
+pub fn create_yd_extended_api(config_filename: &str) -> YDApiHandle<YDExtendedApi> {
+    try_create_yd_extended_api(config_filename).unwrap_or_else(|error| panic!("{error}"))
+}
+
+#[cfg(not(feature = "dlopen"))]
+pub fn try_create_yd_extended_api_and_spi(
+    config_filename: &str,
+) -> Result<YDExtendedApiAndSpi, CreateApiError> {
+    start_extended_spi(try_create_yd_extended_api(config_filename)?)
+}
+
+/// same as `try_create_yd_api_with_ring`, both listeners get a ring of `ring_config`
+#[cfg(not(feature = "dlopen"))]
+pub fn try_create_yd_extended_api_with_ring(
+    config_filename: &str,
+    ring_config: SpiRingConfig,
+) -> Result<YDExtendedApiAndRing, CreateApiError> {
+    start_extended_ring(try_create_yd_extended_api(config_filename)?, ring_config)
+}
+
+#[cfg(not(feature = "dlopen"))]
+pub fn create_yd_extended_api_and_spi(
+    config_filename: &str,
+) -> (
+    YDApiHandle<YDExtendedApi>,
+    Box<YDListenerStream>,
+    Box<YDExtendedListenerStream>,
+) {
+    try_create_yd_extended_api_and_spi(config_filename).unwrap_or_else(|error| panic!("{error}"))
+}
@@ -0,0 +1,31 @@
+use std::fmt;
+
+/// reasons why `insert_multi_orders` and its siblings don't call libyd, `ErrorNo` of every element is set as well
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultiRequestError {
+    /// more orders or order cancels than the maximum count in ydApi.h, `ErrorNo` is `YD_ERROR_TooManyInMultiOrders`
+    TooManyInMultiOrders { count: usize, max_count: usize },
+    /// more quotes or quote cancels than the maximum count in ydApi.h. ydError.h has no error code for it,
+    /// so `ErrorNo` is `YD_ERROR_QuoteFieldError` or `YD_ERROR_CancelQuoteFieldError`
+    TooManyInMultiQuotes { count: usize, max_count: usize },
+    /// instruments or exchanges don't have the same length as the elements, `ErrorNo` is the field error of the element
+    LengthMismatch { count: usize, target_count: usize },
+}
+
+impl fmt::Display for MultiRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManyInMultiOrders { count, max_count } => {
+                write!(f, "{count} orders exceed the maximum count {max_count} of a multi request")
+            }
+            Self::TooManyInMultiQuotes { count, max_count } => {
+                write!(f, "{count} quotes exceed the maximum count {max_count} of a multi request")
+            }
+            Self::LengthMismatch { count, target_count } => {
+                write!(f, "{count} elements are given with {target_count} instruments or exchanges")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MultiRequestError {}
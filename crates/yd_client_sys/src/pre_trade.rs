@@ -0,0 +1,232 @@
+//! local pre-trade checks of `YDInputOrder` against the static data of libyd, nothing is sent to ydServer
+
+use std::fmt;
+
+use crate::{
+    bindings::{YDAccountInstrumentInfo, YDInputOrder, YDInstrument},
+    data_types::{Direction, OffsetFlag, OrderFlag, OrderType, TradeRight},
+    yd_error::YdError,
+};
+
+/// prices within this fraction of a tick are treated as equal
+const TICK_TOLERANCE: f64 = 1e-6;
+
+/// the same rejections ydServer would return, see `yd_error` for the corresponding `YdError`
+#[derive(Debug, Clone, PartialEq)]
+pub enum PreTradeError {
+    /// price is not a multiple of `YDInstrument::Tick`
+    PriceTickError { price: f64, tick: f64 },
+    /// price is out of `LowerLimitPrice` and `UpperLimitPrice` of the market data
+    PriceOutOfLimit {
+        price: f64,
+        lower_limit_price: f64,
+        upper_limit_price: f64,
+    },
+    /// volume is out of `MinLimitOrderVolume`-`MaxLimitOrderVolume`, or the market order ones
+    InvalidOrderVolume { volume: i32, min_volume: i32, max_volume: i32 },
+    /// `Forbidden`, or opening a position with `CloseOnly`
+    NoTradingRight { trading_right: TradeRight },
+    /// `OpenLimit` or `DirectionOpenLimit` of `TradingConstraints`
+    OrderOpenLimitExceed { open_volume: i32, open_limit: i32 },
+    /// `PositionLimit` or `DirectionPositionLimit` of `TradingConstraints`
+    PositionLimitExceed { position: i32, position_limit: i32 },
+}
+
+impl PreTradeError {
+    pub fn yd_error(&self) -> YdError {
+        match self {
+            Self::PriceTickError { .. } => YdError::PriceTickError,
+            Self::PriceOutOfLimit { .. } => YdError::PriceOutOfLimit,
+            Self::InvalidOrderVolume { .. } => YdError::InvalidOrderVolume,
+            Self::NoTradingRight { .. } => YdError::NoTradingRight,
+            Self::OrderOpenLimitExceed { .. } => YdError::OrderOpenLimitExceed,
+            Self::PositionLimitExceed { .. } => YdError::PositionLimitExceed,
+        }
+    }
+}
+
+impl fmt::Display for PreTradeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PriceTickError { price, tick } => write!(f, "price {price} is not a multiple of tick {tick}"),
+            Self::PriceOutOfLimit {
+                price,
+                lower_limit_price,
+                upper_limit_price,
+            } => write!(f, "price {price} is out of {lower_limit_price}-{upper_limit_price}"),
+            Self::InvalidOrderVolume {
+                volume,
+                min_volume,
+                max_volume,
+            } => write!(f, "order volume {volume} is out of {min_volume}-{max_volume}"),
+            Self::NoTradingRight { trading_right } => write!(f, "no trading right, the account is {trading_right}"),
+            Self::OrderOpenLimitExceed { open_volume, open_limit } => {
+                write!(f, "open volume {open_volume} exceeds the open limit {open_limit}")
+            }
+            Self::PositionLimitExceed { position, position_limit } => {
+                write!(f, "position {position} exceeds the position limit {position_limit}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PreTradeError {}
+
+/// volumes of the account on the instrument and hedge flag before the order, tracked by the caller.
+/// Both arrays are indexed by `Direction` of the opening orders, i.e. buy for long and sell for short
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TradingUsage {
+    /// volume opened today in both directions
+    pub open_volume: i32,
+    pub direction_open_volume: [i32; 2],
+    pub position: i32,
+    pub direction_position: [i32; 2],
+}
+
+/// checks `YD_YOF_Normal` orders of an instrument, orders of other `YDOrderFlag` are passed through.
+///
+/// `check` reads the limit prices through `m_pMarketData` of the instrument, so `new` is unsafe and the instrument
+/// is not a public field, use `Catalog::pre_trade_check` for instruments of the api.
+#[derive(Debug, Clone, Copy)]
+pub struct PreTradeCheck<'a> {
+    instrument: &'a YDInstrument,
+    /// `Catalog::account_instrument_info`, `None` skips `TradingRight` and `TradingConstraints`
+    pub account_instrument_info: Option<&'a YDAccountInstrumentInfo>,
+    /// `None` skips `TradingConstraints`
+    pub usage: Option<TradingUsage>,
+}
+
+impl<'a> PreTradeCheck<'a> {
+    /// # Safety
+    ///
+    /// `m_pMarketData` of `instrument` must be null or valid while the check is used, that is, the instrument is
+    /// given by libyd and the api is not destroyed, or the caller keeps the market data alive
+    pub unsafe fn new(instrument: &'a YDInstrument) -> Self {
+        Self {
+            instrument,
+            account_instrument_info: None,
+            usage: None,
+        }
+    }
+
+    pub fn instrument(&self) -> &'a YDInstrument {
+        self.instrument
+    }
+
+    pub fn check(&self, input_order: &YDInputOrder) -> Result<(), PreTradeError> {
+        if input_order.yd_order_flag() != Ok(OrderFlag::Normal) {
+            return Ok(());
+        }
+        let is_market = input_order.order_type() == Ok(OrderType::Market);
+        if !is_market {
+            self.check_price(unsafe { input_order.__bindgen_anon_1.Price })?;
+        }
+        self.check_volume(input_order.OrderVolume, is_market)?;
+        let Some(info) = self.account_instrument_info else {
+            return Ok(());
+        };
+        // 只限制开仓，平仓总是允许的
+        let is_open = input_order.offset_flag() == Ok(OffsetFlag::Open);
+        match info.trading_right() {
+            Ok(trading_right @ TradeRight::Forbidden) => return Err(PreTradeError::NoTradingRight { trading_right }),
+            Ok(trading_right @ TradeRight::CloseOnly) if is_open => {
+                return Err(PreTradeError::NoTradingRight { trading_right })
+            }
+            _ => {}
+        }
+        match (self.usage, is_open) {
+            (Some(usage), true) => check_trading_constraints(info, input_order, &usage),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_price(&self, price: f64) -> Result<(), PreTradeError> {
+        let tick = self.instrument.Tick;
+        if tick > 0.0 {
+            let ticks = price / tick;
+            if (ticks - ticks.round()).abs() > TICK_TOLERANCE {
+                return Err(PreTradeError::PriceTickError { price, tick });
+            }
+        }
+        // 没有行情时不检查涨跌停价。`new` 要求 m_pMarketData 为空或有效
+        let limit_prices = unsafe { (self.instrument.get_lower_limit_price(), self.instrument.get_upper_limit_price()) };
+        let (Some(lower_limit_price), Some(upper_limit_price)) = limit_prices else {
+            return Ok(());
+        };
+        let tolerance = tick.max(0.0) * TICK_TOLERANCE;
+        if price < lower_limit_price - tolerance || price > upper_limit_price + tolerance {
+            return Err(PreTradeError::PriceOutOfLimit {
+                price,
+                lower_limit_price,
+                upper_limit_price,
+            });
+        }
+        Ok(())
+    }
+
+    fn check_volume(&self, volume: i32, is_market: bool) -> Result<(), PreTradeError> {
+        let instrument = self.instrument;
+        let (min_volume, max_volume) = if is_market {
+            (instrument.MinMarketOrderVolume, instrument.MaxMarketOrderVolume)
+        } else {
+            (instrument.MinLimitOrderVolume, instrument.MaxLimitOrderVolume)
+        };
+        if volume <= 0 || volume < min_volume || volume > max_volume {
+            return Err(PreTradeError::InvalidOrderVolume {
+                volume,
+                min_volume,
+                max_volume,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// negative limits are not set
+fn exceeds(current: i32, volume: i32, limit: i32) -> bool {
+    limit >= 0 && current.saturating_add(volume) > limit
+}
+
+fn check_trading_constraints(
+    info: &YDAccountInstrumentInfo,
+    input_order: &YDInputOrder,
+    usage: &TradingUsage,
+) -> Result<(), PreTradeError> {
+    // TradingConstraints 和 m_pMarginRate 一样按 HedgeFlag - 1 索引
+    let Some(constraint) = usize::try_from(input_order.HedgeFlag)
+        .ok()
+        .and_then(|hedge_flag| hedge_flag.checked_sub(1))
+        .and_then(|index| info.TradingConstraints.get(index))
+    else {
+        return Ok(());
+    };
+    let direction = match input_order.direction() {
+        Ok(Direction::Buy) => 0,
+        Ok(Direction::Sell) => 1,
+        _ => return Ok(()),
+    };
+    let volume = input_order.OrderVolume;
+    for (open_volume, open_limit) in [
+        (usage.open_volume, constraint.OpenLimit),
+        (usage.direction_open_volume[direction], constraint.DirectionOpenLimit[direction]),
+    ] {
+        if exceeds(open_volume, volume, open_limit) {
+            return Err(PreTradeError::OrderOpenLimitExceed {
+                open_volume: open_volume.saturating_add(volume),
+                open_limit,
+            });
+        }
+    }
+    for (position, position_limit) in [
+        (usage.position, constraint.PositionLimit),
+        (usage.direction_position[direction], constraint.DirectionPositionLimit[direction]),
+    ] {
+        if exceeds(position, volume, position_limit) {
+            return Err(PreTradeError::PositionLimitExceed {
+                position: position.saturating_add(volume),
+                position_limit,
+            });
+        }
+    }
+    Ok(())
+}
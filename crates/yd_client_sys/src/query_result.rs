@@ -0,0 +1,162 @@
+use std::{
+    ffi::CStr,
+    iter::FusedIterator,
+    marker::PhantomData,
+    os::raw::{c_char, c_int},
+    ptr::NonNull,
+};
+
+/// C++ `YDQueryResult<T>` in ydDataStruct.h, only its v-table is used
+#[repr(C)]
+pub struct RawQueryResult<T> {
+    vtable: *const RawQueryResultVTable<T>,
+}
+
+/// 虚析构函数的位置同 api_wrapper 中的 v-table，见 `handle_destructor`
+#[repr(C)]
+struct RawQueryResultVTable<T> {
+    destructor: unsafe extern "C" fn(this: *mut RawQueryResult<T>),
+    #[cfg(not(target_os = "windows"))]
+    deleting_destructor: unsafe extern "C" fn(this: *mut RawQueryResult<T>),
+    get_count: unsafe extern "C" fn(this: *const RawQueryResult<T>) -> c_int,
+    get: unsafe extern "C" fn(this: *const RawQueryResult<T>, pos: c_int) -> *const T,
+    destroy: unsafe extern "C" fn(this: *mut RawQueryResult<T>),
+}
+
+/// returned by `getConfigs` and the `find*` methods of `YDExtendedApi`, `destroy` is called on drop.
+///
+/// Elements may point into data owned by the api, so the result borrows the api it comes from.
+pub struct QueryResult<'a, T> {
+    raw: NonNull<RawQueryResult<T>>,
+    count: usize,
+    /// positions for which `get` returns non-null, the result doesn't change until `destroy`
+    non_null_count: usize,
+    _api: PhantomData<&'a T>,
+}
+
+unsafe impl<T: Sync> Send for QueryResult<'_, T> {}
+
+impl<T> QueryResult<'_, T> {
+    /// # Safety
+    ///
+    /// `raw` must be returned by libyd and not destroyed yet, `None` for null
+    pub unsafe fn from_raw(raw: *mut RawQueryResult<T>) -> Option<Self> {
+        let raw = NonNull::new(raw)?;
+        let count = ((*raw.as_ref().vtable).get_count)(raw.as_ptr());
+        let mut result = Self {
+            raw,
+            count: count.max(0) as usize,
+            non_null_count: 0,
+            _api: PhantomData,
+        };
+        result.non_null_count = (0..result.count).filter(|&pos| result.get(pos).is_some()).count();
+        Some(result)
+    }
+
+    /// `getCount`, elements for which `get` returns null are counted as well, unlike `iter().len()`
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// `get(pos)`, `None` if `pos` is out of range or libyd returns null
+    pub fn get(&self, pos: usize) -> Option<&T> {
+        if pos >= self.count {
+            return None;
+        }
+        unsafe {
+            let raw = self.raw.as_ptr();
+            ((*(*raw).vtable).get)(raw, pos as c_int).as_ref()
+        }
+    }
+
+    pub fn iter(&self) -> QueryResultIter<'_, T> {
+        QueryResultIter {
+            result: self,
+            front: 0,
+            back: self.count,
+            remaining: self.non_null_count,
+        }
+    }
+}
+
+/// `getConfigs` returns C strings
+impl QueryResult<'_, c_char> {
+    pub fn iter_cstr(&self) -> impl DoubleEndedIterator<Item = &CStr> + ExactSizeIterator {
+        self.iter().map(|value| unsafe { CStr::from_ptr(value) })
+    }
+
+    /// configs are ASCII in practice, invalid UTF-8 is replaced
+    pub fn to_strings(&self) -> Vec<String> {
+        self.iter_cstr()
+            .map(|value| value.to_string_lossy().into_owned())
+            .collect()
+    }
+}
+
+impl<T> Drop for QueryResult<'_, T> {
+    fn drop(&mut self) {
+        unsafe {
+            let raw = self.raw.as_ptr();
+            ((*(*raw).vtable).destroy)(raw);
+        }
+    }
+}
+
+impl<'r, T> IntoIterator for &'r QueryResult<'_, T> {
+    type Item = &'r T;
+    type IntoIter = QueryResultIter<'r, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// elements of a `QueryResult`, positions for which libyd returns null are skipped like `CatalogIter`.
+/// Its length is counted without them when the result is created, so it can be less than `QueryResult::len`
+pub struct QueryResultIter<'r, T> {
+    result: &'r QueryResult<'r, T>,
+    front: usize,
+    back: usize,
+    /// non-null elements in `front..back`
+    remaining: usize,
+}
+
+impl<'r, T> Iterator for QueryResultIter<'r, T> {
+    type Item = &'r T;
+
+    fn next(&mut self) -> Option<&'r T> {
+        while self.front < self.back {
+            self.front += 1;
+            if let Some(item) = self.result.get(self.front - 1) {
+                self.remaining -= 1;
+                return Some(item);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'r, T> DoubleEndedIterator for QueryResultIter<'r, T> {
+    fn next_back(&mut self) -> Option<&'r T> {
+        while self.front < self.back {
+            self.back -= 1;
+            if let Some(item) = self.result.get(self.back) {
+                self.remaining -= 1;
+                return Some(item);
+            }
+        }
+        None
+    }
+}
+
+impl<T> ExactSizeIterator for QueryResultIter<'_, T> {}
+
+impl<T> FusedIterator for QueryResultIter<'_, T> {}
@@ -0,0 +1,219 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Condvar, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+/// what to do when a bounded queue is full. Trading events like orders and trades are never dropped,
+/// if there is no market data to drop, the queue grows over its capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// drop the oldest queued market data
+    #[default]
+    DropOldestMarketData,
+    /// replace the queued market data of the same instrument, or drop the oldest market data if there is none
+    CoalesceMarketData,
+    /// block the libyd callback thread until the stream takes an event or is dropped
+    Block,
+}
+
+/// passed to `create_spi_with_queue` and `create_extended_spi_with_queue`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SpiQueueConfig {
+    /// `None` for unbounded
+    pub capacity: Option<usize>,
+    pub overflow_policy: OverflowPolicy,
+}
+
+impl SpiQueueConfig {
+    pub fn bounded(capacity: usize, overflow_policy: OverflowPolicy) -> Self {
+        Self {
+            capacity: Some(capacity),
+            overflow_policy,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SpiQueueMetrics {
+    /// events waiting to be taken from the stream
+    pub depth: usize,
+    /// market data dropped or coalesced by the overflow policy, and events received after the stream is dropped
+    pub dropped: u64,
+    /// the maximum depth so far
+    pub high_water_mark: usize,
+}
+
+/// listener outputs, market data can be dropped or coalesced when the queue is full
+pub trait SpiQueueItem {
+    /// `InstrumentRef` of market data, `None` for other events
+    fn market_data_instrument_ref(&self) -> Option<i32>;
+}
+
+/// where the generated listener puts its outputs, `SpiQueue` for the async stream and `RingProducer` for busy polling
+pub trait SpiSink<T> {
+    fn push(&mut self, item: T);
+}
+
+struct QueueState<T> {
+    buf: VecDeque<T>,
+    waker: Option<Waker>,
+    /// the stream is dropped, nobody will take events anymore
+    closed: bool,
+    /// the listener is released by libyd, e.g. after `notifyAfterApiDestroy`, nobody will push events anymore
+    sender_closed: bool,
+    dropped: u64,
+    high_water_mark: usize,
+}
+
+impl<T: SpiQueueItem> QueueState<T> {
+    fn drop_oldest_market_data(&mut self) {
+        if let Some(index) = self.buf.iter().position(|item| item.market_data_instrument_ref().is_some()) {
+            self.buf.remove(index);
+            self.dropped += 1;
+        }
+    }
+
+    /// replace the latest market data of the same instrument, returns the item back if there is none
+    fn coalesce_market_data(&mut self, item: T) -> Option<T> {
+        let Some(instrument_ref) = item.market_data_instrument_ref() else {
+            return Some(item);
+        };
+        match self
+            .buf
+            .iter_mut()
+            .rev()
+            .find(|queued| queued.market_data_instrument_ref() == Some(instrument_ref))
+        {
+            Some(queued) => {
+                *queued = item;
+                self.dropped += 1;
+                None
+            }
+            None => Some(item),
+        }
+    }
+}
+
+/// shared by the listener registered to libyd and the stream
+pub struct SpiQueue<T> {
+    config: SpiQueueConfig,
+    state: Mutex<QueueState<T>>,
+    not_full: Condvar,
+}
+
+impl<T: SpiQueueItem> SpiQueue<T> {
+    pub fn new(config: SpiQueueConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(QueueState {
+                buf: VecDeque::new(),
+                waker: None,
+                closed: false,
+                sender_closed: false,
+                dropped: 0,
+                high_water_mark: 0,
+            }),
+            not_full: Condvar::new(),
+        }
+    }
+
+    /// called by the listener on the libyd callback thread
+    pub fn push(&self, item: T) {
+        let mut state = self.state.lock().unwrap();
+        let mut item = Some(item);
+        if let Some(capacity) = self.config.capacity {
+            if !state.closed && state.buf.len() >= capacity {
+                match self.config.overflow_policy {
+                    OverflowPolicy::DropOldestMarketData => state.drop_oldest_market_data(),
+                    OverflowPolicy::CoalesceMarketData => {
+                        item = state.coalesce_market_data(item.take().unwrap());
+                        if item.is_some() {
+                            state.drop_oldest_market_data();
+                        }
+                    }
+                    OverflowPolicy::Block => {
+                        state = self
+                            .not_full
+                            .wait_while(state, |state| !state.closed && state.buf.len() >= capacity)
+                            .unwrap();
+                    }
+                }
+            }
+        }
+        let Some(item) = item else {
+            return;
+        };
+        if state.closed {
+            state.dropped += 1;
+            return;
+        }
+        state.buf.push_back(item);
+        state.high_water_mark = state.high_water_mark.max(state.buf.len());
+        if let Some(waker) = state.waker.take() {
+            waker.wake()
+        }
+    }
+
+    /// `Ready(None)` once the sender is closed and the queued events are all taken
+    pub fn poll_pop(&self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(item) = state.buf.pop_front() {
+            self.not_full.notify_one();
+            Poll::Ready(Some(item))
+        } else if state.closed || state.sender_closed {
+            Poll::Ready(None)
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+
+    pub fn metrics(&self) -> SpiQueueMetrics {
+        let state = self.state.lock().unwrap();
+        SpiQueueMetrics {
+            depth: state.buf.len(),
+            dropped: state.dropped,
+            high_water_mark: state.high_water_mark,
+        }
+    }
+
+    /// the stream is dropped, release queued events and wake up the blocked callback
+    pub fn close(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        state.buf.clear();
+        self.not_full.notify_all();
+    }
+
+    /// the listener is released, the stream ends after the queued events are taken
+    pub fn close_sender(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.sender_closed = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake()
+        }
+    }
+}
+
+/// owned by the listener registered to libyd, closes the sender side of the queue when the listener is dropped
+pub struct SpiQueueSender<T: SpiQueueItem>(Arc<SpiQueue<T>>);
+
+impl<T: SpiQueueItem> SpiQueueSender<T> {
+    pub fn new(queue: Arc<SpiQueue<T>>) -> Self {
+        Self(queue)
+    }
+}
+
+impl<T: SpiQueueItem> SpiSink<T> for SpiQueueSender<T> {
+    fn push(&mut self, item: T) {
+        self.0.push(item)
+    }
+}
+
+impl<T: SpiQueueItem> Drop for SpiQueueSender<T> {
+    fn drop(&mut self) {
+        // libyd 不会再调用 listener，让 stream 取完剩下的事件后结束
+        self.0.close_sender();
+    }
+}
@@ -0,0 +1,237 @@
+use std::{
+    cell::UnsafeCell,
+    collections::VecDeque,
+    mem::MaybeUninit,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::spi_queue::{SpiQueueItem, SpiSink};
+
+/// what to do with market data when the ring is full. Trading events like orders and trades are never dropped
+/// and never wait, they are moved to a spill queue, which the consumer drains after the ring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RingOverflowPolicy {
+    /// drop the new market data
+    #[default]
+    DropMarketData,
+    /// spin on the libyd callback thread until the consumer takes an event, so no market data is lost
+    SpinOnMarketData,
+}
+
+/// passed to `create_spi_with_ring`, `try_create_yd_api_with_ring` and their siblings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpiRingConfig {
+    /// slots allocated up front, must be positive
+    pub capacity: usize,
+    pub overflow_policy: RingOverflowPolicy,
+}
+
+impl SpiRingConfig {
+    pub fn new(capacity: usize, overflow_policy: RingOverflowPolicy) -> Self {
+        Self {
+            capacity,
+            overflow_policy,
+        }
+    }
+}
+
+/// keep the indices written by producer and consumer in different cache lines
+#[repr(align(128))]
+struct CachePadded<T>(T);
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+struct Ring<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    /// next slot to read, only written by consumer
+    head: CachePadded<AtomicUsize>,
+    /// next slot to write, only written by producer
+    tail: CachePadded<AtomicUsize>,
+    dropped: AtomicU64,
+    consumer_alive: AtomicBool,
+    /// false once the listener owning the producer is released by libyd
+    producer_alive: AtomicBool,
+    /// events pushed while the ring is full, they are all newer than the events in the ring
+    spill: Mutex<VecDeque<T>>,
+    /// length of `spill`, so neither side locks it while nothing is spilled
+    spilled: AtomicUsize,
+}
+
+unsafe impl<T: Send> Sync for Ring<T> {}
+
+impl<T> Drop for Ring<T> {
+    fn drop(&mut self) {
+        let head = *self.head.0.get_mut();
+        let tail = *self.tail.0.get_mut();
+        let mut index = head;
+        while index != tail {
+            unsafe { self.slots[index % self.slots.len()].get_mut().assume_init_drop() };
+            index = index.wrapping_add(1);
+        }
+    }
+}
+
+/// pre-allocated single-producer/single-consumer ring, pushing and popping never lock or allocate until the ring is full
+pub fn spsc_ring<T>(config: SpiRingConfig) -> (RingProducer<T>, RingConsumer<T>) {
+    assert!(config.capacity > 0, "capacity of ring must be positive");
+    let ring = Arc::new(Ring {
+        slots: (0..config.capacity).map(|_| UnsafeCell::new(MaybeUninit::uninit())).collect(),
+        head: CachePadded(AtomicUsize::new(0)),
+        tail: CachePadded(AtomicUsize::new(0)),
+        dropped: AtomicU64::new(0),
+        consumer_alive: AtomicBool::new(true),
+        producer_alive: AtomicBool::new(true),
+        spill: Mutex::new(VecDeque::new()),
+        spilled: AtomicUsize::new(0),
+    });
+    let producer = RingProducer {
+        ring: ring.clone(),
+        overflow_policy: config.overflow_policy,
+    };
+    (producer, RingConsumer { ring })
+}
+
+/// owned by the listener registered to libyd, pushes on the libyd callback thread
+pub struct RingProducer<T> {
+    ring: Arc<Ring<T>>,
+    overflow_policy: RingOverflowPolicy,
+}
+
+impl<T> RingProducer<T> {
+    /// returns the item back when the ring is full, or events are waiting in the spill queue,
+    /// which must be taken before anything newer is put into the ring
+    pub fn try_push(&mut self, item: T) -> Result<(), T> {
+        let ring = &*self.ring;
+        if ring.spilled.load(Ordering::Acquire) != 0 {
+            return Err(item);
+        }
+        let tail = ring.tail.load(Ordering::Relaxed);
+        let head = ring.head.load(Ordering::Acquire);
+        if tail.wrapping_sub(head) == ring.slots.len() {
+            return Err(item);
+        }
+        unsafe { (*ring.slots[tail % ring.slots.len()].get()).write(item) };
+        ring.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    /// only the producer adds to the spill queue, so `try_push` keeps failing until the consumer empties it
+    fn spill(&mut self, item: T) {
+        let mut spill = self.ring.spill.lock().unwrap();
+        spill.push_back(item);
+        self.ring.spilled.store(spill.len(), Ordering::Release);
+    }
+}
+
+/// When the ring is full, market data is dropped or waited for by `RingOverflowPolicy`, other events are spilled,
+/// which locks and allocates on the callback thread. Events are dropped once the consumer is dropped.
+impl<T: SpiQueueItem> SpiSink<T> for RingProducer<T> {
+    fn push(&mut self, item: T) {
+        let mut item = item;
+        loop {
+            item = match self.try_push(item) {
+                Ok(()) => return,
+                Err(back) => back,
+            };
+            if !self.ring.consumer_alive.load(Ordering::Acquire) {
+                self.ring.dropped.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+            if item.market_data_instrument_ref().is_none() {
+                self.spill(item);
+                return;
+            }
+            match self.overflow_policy {
+                RingOverflowPolicy::DropMarketData => {
+                    self.ring.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                RingOverflowPolicy::SpinOnMarketData => std::hint::spin_loop(),
+            }
+        }
+    }
+}
+
+impl<T> Drop for RingProducer<T> {
+    fn drop(&mut self) {
+        self.ring.producer_alive.store(false, Ordering::Release);
+    }
+}
+
+/// busy-poll it from a pinned thread, e.g. the one given by `TCPTradingCPUID`
+pub struct RingConsumer<T> {
+    ring: Arc<Ring<T>>,
+}
+
+impl<T> RingConsumer<T> {
+    pub fn pop(&mut self) -> Option<T> {
+        let ring = &*self.ring;
+        // 环中的事件都比溢出队列中的早，环空了才取溢出队列。先读 spilled，
+        // 这样溢出之前写入环的事件一定能在下面读 tail 时看到
+        let spilled = ring.spilled.load(Ordering::Acquire);
+        let head = ring.head.load(Ordering::Relaxed);
+        if head != ring.tail.load(Ordering::Acquire) {
+            let item = unsafe { (*ring.slots[head % ring.slots.len()].get()).assume_init_read() };
+            ring.head.store(head.wrapping_add(1), Ordering::Release);
+            return Some(item);
+        }
+        if spilled == 0 {
+            return None;
+        }
+        let mut spill = ring.spill.lock().unwrap();
+        let item = spill.pop_front();
+        ring.spilled.store(spill.len(), Ordering::Release);
+        item
+    }
+
+    /// events in the ring and the spill queue
+    pub fn len(&self) -> usize {
+        let tail = self.ring.tail.load(Ordering::Acquire);
+        tail.wrapping_sub(self.ring.head.load(Ordering::Relaxed)) + self.ring.spilled.load(Ordering::Acquire)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.ring.slots.len()
+    }
+
+    /// trading events waiting in the spill queue, non-zero means the consumer has fallen behind
+    pub fn spilled(&self) -> usize {
+        self.ring.spilled.load(Ordering::Acquire)
+    }
+
+    /// market data dropped by `RingOverflowPolicy::DropMarketData`, and events pushed after the consumer is dropped
+    pub fn dropped(&self) -> u64 {
+        self.ring.dropped.load(Ordering::Relaxed)
+    }
+
+    /// the listener is released by libyd, e.g. after `notifyAfterApiDestroy`, and all events are taken,
+    /// `pop` will never return an event again
+    pub fn is_closed(&self) -> bool {
+        // 先读 producer_alive，producer 在 drop 之前写入的事件一定能在下面读 tail 和 spilled 时看到
+        !self.ring.producer_alive.load(Ordering::Acquire) && self.is_empty()
+    }
+}
+
+impl<T> Drop for RingConsumer<T> {
+    fn drop(&mut self) {
+        // 不再有人消费事件，之后的事件直接丢弃：行情不再自旋等待，交易事件也不再进入溢出队列
+        self.ring.consumer_alive.store(false, Ordering::Release);
+        let mut spill = self.ring.spill.lock().unwrap();
+        spill.clear();
+        self.ring.spilled.store(0, Ordering::Release);
+    }
+}
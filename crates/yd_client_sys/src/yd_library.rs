@@ -0,0 +1,169 @@
+use std::{
+    ffi::{c_char, c_ulonglong, OsStr},
+    fmt,
+    path::PathBuf,
+    sync::Arc,
+};
+
+use crate::{
+    bindings::{YDApi, YDExtendedApi},
+    create_api, start_extended_ring, start_extended_spi, start_listener, start_ring, start_spi,
+    spi_wrapper::{YDListenerRingConsumer, YDListenerStream, YDListenerTrait},
+    version_from_ptr, CreateApiError, SpiRingConfig, YDApiHandle, YDExtendedApiAndRing, YDExtendedApiAndSpi,
+};
+
+/// reasons why `YdLibrary::load` fails
+#[derive(Debug)]
+pub enum LoadLibraryError {
+    /// the file is missing, or it is not a shared library of the current platform
+    Library { path: PathBuf, source: libloading::Error },
+    /// the library is loaded, but it is not libyd, or its version is too old
+    Symbol {
+        path: PathBuf,
+        symbol: &'static str,
+        source: libloading::Error,
+    },
+}
+
+impl fmt::Display for LoadLibraryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Library { path, source } => write!(f, "failed to load libyd from {}: {source}", path.display()),
+            Self::Symbol { path, symbol, source } => {
+                write!(f, "symbol {symbol} is not found in {}: {source}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadLibraryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Library { source, .. } | Self::Symbol { source, .. } => Some(source),
+        }
+    }
+}
+
+struct LoadedLibrary {
+    make_yd_api: unsafe extern "C" fn(*const c_char) -> *mut YDApi,
+    make_yd_extended_api: unsafe extern "C" fn(*const c_char) -> *mut YDExtendedApi,
+    get_yd_version: unsafe extern "C" fn() -> *const c_char,
+    get_yd_nano_timestamp: unsafe extern "C" fn() -> c_ulonglong,
+    // 函数指针只在 library 被卸载前有效，所以和它放在一起
+    _library: libloading::Library,
+}
+
+/// libyd loaded at runtime by the `dlopen` feature, instead of being linked with an rpath into the source tree.
+///
+/// Cloning is cheap. The library is unloaded when all clones are dropped, an api created from it keeps it loaded
+/// until the process exits, because libyd may still be destroying the api after `startDestroy` returns.
+#[derive(Clone)]
+pub struct YdLibrary {
+    inner: Arc<LoadedLibrary>,
+}
+
+impl YdLibrary {
+    /// `path` is passed to `dlopen` or `LoadLibraryW`, e.g. `/opt/ydClient_1_386_40_0/ydAPI_c++/linux64/libyd.so`
+    pub fn load(path: impl AsRef<OsStr>) -> Result<Self, LoadLibraryError> {
+        let path = PathBuf::from(path.as_ref());
+        let library = unsafe { libloading::Library::new(&path) }.map_err(|source| LoadLibraryError::Library {
+            path: path.clone(),
+            source,
+        })?;
+        macro_rules! symbol {
+            ($name: literal) => {
+                *unsafe { library.get(concat!($name, "\0").as_bytes()) }.map_err(|source| LoadLibraryError::Symbol {
+                    path: path.clone(),
+                    symbol: $name,
+                    source,
+                })?
+            };
+        }
+        let make_yd_api = symbol!("makeYDApi");
+        let make_yd_extended_api = symbol!("makeYDExtendedApi");
+        let get_yd_version = symbol!("getYDVersion");
+        let get_yd_nano_timestamp = symbol!("getYDNanoTimestamp");
+        Ok(Self {
+            inner: Arc::new(LoadedLibrary {
+                make_yd_api,
+                make_yd_extended_api,
+                get_yd_version,
+                get_yd_nano_timestamp,
+                _library: library,
+            }),
+        })
+    }
+
+    /// `getYDVersion`, empty if libyd returns null
+    pub fn version(&self) -> String {
+        version_from_ptr(unsafe { (self.inner.get_yd_version)() })
+    }
+
+    /// `getYDNanoTimestamp`, nanoseconds elapsed since current process starts up
+    pub fn nano_timestamp(&self) -> u64 {
+        unsafe { (self.inner.get_yd_nano_timestamp)() }
+    }
+
+    /// same as `check_yd_version` of the linked mode
+    pub fn check_version(&self) -> Result<(), CreateApiError> {
+        crate::check_version(self.version())
+    }
+
+    pub fn try_create_yd_api(&self, config_filename: &str) -> Result<YDApiHandle<YDApi>, CreateApiError> {
+        self.check_version()?;
+        let api = create_api(self.inner.make_yd_api, config_filename)?;
+        Ok(api.keep_library(self.clone()))
+    }
+
+    pub fn try_create_yd_api_and_spi(
+        &self,
+        config_filename: &str,
+    ) -> Result<(YDApiHandle<YDApi>, Box<YDListenerStream>), CreateApiError> {
+        start_spi(self.try_create_yd_api(config_filename)?)
+    }
+
+    /// see `try_create_yd_api_with_ring` of the linked mode
+    pub fn try_create_yd_api_with_ring(
+        &self,
+        config_filename: &str,
+        ring_config: SpiRingConfig,
+    ) -> Result<(YDApiHandle<YDApi>, YDListenerRingConsumer), CreateApiError> {
+        start_ring(self.try_create_yd_api(config_filename)?, ring_config)
+    }
+
+    /// see `YDApiHandle::start_with_listener`
+    pub fn try_create_yd_api_with_listener<L>(
+        &self,
+        config_filename: &str,
+        listener: Box<L>,
+    ) -> Result<YDApiHandle<YDApi>, CreateApiError>
+    where
+        L: for<'a> YDListenerTrait<'a> + Send + 'static,
+    {
+        start_listener(self.try_create_yd_api(config_filename)?, listener)
+    }
+
+    pub fn try_create_yd_extended_api(
+        &self,
+        config_filename: &str,
+    ) -> Result<YDApiHandle<YDExtendedApi>, CreateApiError> {
+        self.check_version()?;
+        let api = create_api(self.inner.make_yd_extended_api, config_filename)?;
+        Ok(api.keep_library(self.clone()))
+    }
+
+    pub fn try_create_yd_extended_api_and_spi(
+        &self,
+        config_filename: &str,
+    ) -> Result<YDExtendedApiAndSpi, CreateApiError> {
+        start_extended_spi(self.try_create_yd_extended_api(config_filename)?)
+    }
+
+    pub fn try_create_yd_extended_api_with_ring(
+        &self,
+        config_filename: &str,
+        ring_config: SpiRingConfig,
+    ) -> Result<YDExtendedApiAndRing, CreateApiError> {
+        start_extended_ring(self.try_create_yd_extended_api(config_filename)?, ring_config)
+    }
+}
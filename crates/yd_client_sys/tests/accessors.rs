@@ -0,0 +1,89 @@
+use std::os::raw::c_char;
+use yd_client_sys::{
+    bindings::{YDExtendedOrder, YDExtendedPosition, YDExtendedPosition_CPositionDetail, YDInstrument, YDMarketData, YDOrder},
+    data_types::{Direction, MarketDataFlag, OrderFlag, OrderStatus},
+    get_fixed_str,
+};
+
+#[test]
+fn test_get_fixed_str() {
+    let mut buffer = [b'x' as c_char; 4];
+    assert_eq!(get_fixed_str(&buffer), "xxxx");
+    buffer[2] = 0;
+    assert_eq!(get_fixed_str(&buffer), "xx");
+    buffer[0] = -1;
+    assert_eq!(get_fixed_str(&buffer), "");
+}
+
+#[test]
+fn test_instrument_accessors() {
+    let mut instrument = YDInstrument::default();
+    instrument.set_instrument_id("cu2401").unwrap();
+    assert_eq!(instrument.instrument_id(), "cu2401");
+    assert!(unsafe { instrument.market_data() }.is_none());
+    assert_eq!(unsafe { instrument.get_last_price() }, None);
+
+    let market_data = YDMarketData {
+        LastPrice: 68000.0,
+        Volume: 12,
+        MarketDataFlag: 1,
+        ..Default::default()
+    };
+    instrument.m_pMarketData = &market_data;
+    assert_eq!(unsafe { instrument.get_last_price() }, Some(68000.0));
+    assert_eq!(unsafe { instrument.get_volume() }, Some(12));
+    assert_eq!(market_data.market_data_flag().bits(), 1);
+    assert!(MarketDataFlag::all().contains(market_data.market_data_flag()));
+}
+
+#[test]
+fn test_null_pointers() {
+    assert!(unsafe { YDInstrument::default().exchange() }.is_none());
+    assert!(unsafe { YDMarketData::default().instrument() }.is_none());
+    assert!(unsafe { YDExtendedPosition::default().get_account() }.is_none());
+}
+
+#[test]
+fn test_order_accessors() {
+    let mut order = YDExtendedOrder {
+        _base: YDOrder {
+            Direction: Direction::Sell.into(),
+            OrderStatus: OrderStatus::AllTraded.into(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    // YDExtendedOrder derefs to YDOrder
+    assert_eq!(order.direction(), Ok(Direction::Sell));
+    assert_eq!(order.order_status(), Ok(OrderStatus::AllTraded));
+    assert!(unsafe { order.instrument() }.is_none());
+    order._base.OrderStatus = 100;
+    assert!(order.order_status().is_err());
+    order._base.YDOrderFlag = OrderFlag::CombPosition.into();
+    assert!(unsafe { order.comb_position_def() }.is_none());
+}
+
+#[test]
+fn test_position_helpers() {
+    let mut history = YDExtendedPosition_CPositionDetail {
+        Volume: 3,
+        ..Default::default()
+    };
+    history.__bindgen_anon_1.LongTradeID = -1;
+    let mut today = YDExtendedPosition_CPositionDetail {
+        Volume: 2,
+        ..Default::default()
+    };
+    today.__bindgen_anon_1.LongTradeID = 10;
+    history.m_pNext = &mut today;
+    let position = YDExtendedPosition {
+        Position: 5,
+        TotalOpenPrice: 500.0,
+        PositionDetailList: &mut history,
+        ..Default::default()
+    };
+    assert_eq!(position.get_open_price(), 100.0);
+    assert_eq!(unsafe { position.get_yd_position() }, 3);
+    assert_eq!(YDExtendedPosition::default().get_open_price(), 0.0);
+    assert_eq!(unsafe { YDExtendedPosition::default().get_yd_position() }, 0);
+}
@@ -1,11 +1,10 @@
-use std::ffi::CStr;
-use yd_client_sys::{bindings::getYDVersion, create_yd_api_and_spi};
-#[macro_use]
-extern crate lazy_static;
+// with the `dlopen` feature, apis are created by `YdLibrary`, see tests/dlopen.rs
+#![cfg(not(feature = "dlopen"))]
 
-lazy_static! {
-    static ref VERSION: String = "1.386.40.0".to_string();
-}
+use std::ffi::CStr;
+use yd_client_sys::{
+    bindings::getYDVersion, check_yd_version, create_yd_api, create_yd_api_and_spi, YD_SDK_VERSION,
+};
 
 fn get_api_version1() -> Option<String> {
     unsafe {
@@ -22,7 +21,7 @@ fn get_api_version1() -> Option<String> {
 fn test_get_api_version() {
     let version = get_api_version1().expect("Failed to get API version");
     assert!(!version.is_empty(), "API version should not be empty.");
-    assert_eq!(version, *VERSION, "API version should be updated.");
+    assert_eq!(version, YD_SDK_VERSION, "API version should be updated.");
 }
 
 fn get_api_version2(config_filename: &str) -> Option<String> {
@@ -30,14 +29,8 @@ fn get_api_version2(config_filename: &str) -> Option<String> {
     let (mut api, _) = create_yd_api_and_spi(config_filename);
 
     // Call the get_version method
-    unsafe {
-        let version_c_str = api.get_version();
-        if version_c_str.is_null() {
-            None
-        } else {
-            Some(CStr::from_ptr(version_c_str).to_string_lossy().into_owned())
-        }
-    }
+    let version = api.api_mut().ok()?.get_version()?.into_owned();
+    Some(version)
 }
 
 #[test]
@@ -56,7 +49,24 @@ fn test_get_api_version2() {
         "API version should not be empty using getVersion2."
     );
     assert_eq!(
-        version, *VERSION,
+        version, YD_SDK_VERSION,
         "API version should match the expected value using getVersion2."
     );
 }
+
+#[test]
+fn test_check_yd_version() {
+    check_yd_version().expect("libyd should match the SDK selected at build time");
+}
+
+#[test]
+fn test_get_config() {
+    let mut handle = create_yd_api("examples/config.txt");
+    let mut api = handle.api_mut().unwrap();
+    assert_eq!(
+        api.get_config("TradingServerIP").unwrap().as_deref(),
+        Some("127.0.0.1")
+    );
+    assert_eq!(api.get_config("NotExistConfig").unwrap(), None);
+    assert!(api.get_config("Trading\0ServerIP").is_err());
+}
@@ -0,0 +1,10 @@
+#![cfg(not(feature = "dlopen"))]
+use yd_client_sys::{create_yd_api, CatalogNotReady};
+
+#[test]
+fn test_catalog_before_init() {
+    let mut handle = create_yd_api("examples/config.txt");
+    // not started, so notifyFinishInit can't have been received
+    assert_eq!(handle.catalog().err(), Some(CatalogNotReady));
+    assert!(handle.api_and_catalog().is_err());
+}
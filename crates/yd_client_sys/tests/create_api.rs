@@ -0,0 +1,45 @@
+// with the `dlopen` feature, apis are created by `YdLibrary`, see tests/dlopen.rs
+#![cfg(not(feature = "dlopen"))]
+
+use yd_client_sys::{
+    bindings::{YDAccount, YDInstrument, YDOrder},
+    spi_wrapper::YDListenerTrait,
+    try_create_yd_api_and_spi, try_create_yd_api_with_listener, CreateApiError,
+};
+
+#[test]
+fn test_create_api_with_invalid_config() {
+    assert!(matches!(
+        try_create_yd_api_and_spi("examples/not_exist.txt"),
+        Err(CreateApiError::ConfigNotFound(_))
+    ));
+    assert!(matches!(
+        try_create_yd_api_and_spi("examples/config\0.txt"),
+        Err(CreateApiError::InteriorNul(_))
+    ));
+    assert!(matches!(
+        try_create_yd_api_and_spi("examples"),
+        Err(CreateApiError::ConfigUnreadable { .. })
+    ));
+}
+
+struct Strategy {
+    orders: usize,
+}
+
+impl<'a> YDListenerTrait<'a> for Strategy {
+    fn notify_order(&mut self, _p_order: &'a YDOrder, _p_instrument: &'a YDInstrument, _p_account: &'a YDAccount) {
+        self.orders += 1;
+    }
+}
+
+#[test]
+fn test_create_api_with_listener_and_invalid_config() {
+    let mut strategy = Strategy { orders: 0 };
+    strategy.notify_order(&Default::default(), &Default::default(), &Default::default());
+    assert_eq!(strategy.orders, 1);
+    assert!(matches!(
+        try_create_yd_api_with_listener("examples/not_exist.txt", Box::new(strategy)),
+        Err(CreateApiError::ConfigNotFound(_))
+    ));
+}
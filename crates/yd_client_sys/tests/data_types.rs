@@ -0,0 +1,27 @@
+use std::os::raw::c_char;
+use yd_client_sys::data_types::{
+    AccountFlag, CombPositionDirection, Direction, MarginModel, OrderFlag, TradingRightSource,
+};
+
+#[test]
+fn test_direction_depends_on_order_flag() {
+    assert_eq!(Direction::try_from(0), Ok(Direction::Buy));
+    assert_eq!(CombPositionDirection::try_from(0), Ok(CombPositionDirection::Make));
+    assert_eq!(i32::from(Direction::Sell), i32::from(CombPositionDirection::Split));
+    assert!(Direction::try_from(2).is_err());
+}
+
+#[test]
+fn test_char_field_conversion() {
+    let order_flag: c_char = OrderFlag::Cover.into();
+    assert_eq!(OrderFlag::try_from(order_flag), Ok(OrderFlag::Cover));
+    assert_eq!(OrderFlag::Cover.to_string(), "Cover");
+}
+
+#[test]
+fn test_aliases_and_flags() {
+    assert_eq!(MarginModel::NewModelStart, MarginModel::SPBM);
+    assert_eq!(TradingRightSource::COUNT, 4);
+    let flags = AccountFlag::from_bits_retain(0x11);
+    assert!(flags.contains(AccountFlag::SelectConnection | AccountFlag::NotifyOrderAccept));
+}
@@ -0,0 +1,23 @@
+#![cfg(feature = "dlopen")]
+use yd_client_sys::{CreateApiError, LoadLibraryError, YdLibrary, YD_SDK_VERSION};
+
+const LIBRARY_PATH: &str = "thirdparty/ydClient/ydAPI_c++/linux64/libyd.so";
+
+#[test]
+fn test_load_library() {
+    let library = YdLibrary::load(LIBRARY_PATH).expect("Failed to load libyd");
+    assert_eq!(library.version(), YD_SDK_VERSION);
+    library.check_version().expect("libyd should match the SDK selected at build time");
+    assert!(matches!(
+        library.try_create_yd_api_and_spi("examples/not_exist.txt"),
+        Err(CreateApiError::ConfigNotFound(_))
+    ));
+}
+
+#[test]
+fn test_load_missing_library() {
+    assert!(matches!(
+        YdLibrary::load("thirdparty/not_exist/libyd.so"),
+        Err(LoadLibraryError::Library { .. })
+    ));
+}
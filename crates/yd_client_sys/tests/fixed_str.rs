@@ -0,0 +1,27 @@
+use yd_client_sys::{bindings::YDInstrument, gb18030_cstr_to_str_i8, set_fixed_str, FixedStrError};
+
+#[test]
+fn test_set_fixed_str() {
+    let mut buffer = [b'x' as i8; 8];
+    set_fixed_str(&mut buffer, "cu2101").unwrap();
+    assert_eq!(gb18030_cstr_to_str_i8(&buffer), "cu2101");
+    assert_eq!(buffer[6..], [0, 0]);
+    assert_eq!(
+        set_fixed_str(&mut buffer, "IC2212-C"),
+        Err(FixedStrError::TooLong { len: 8, capacity: 8 })
+    );
+    assert_eq!(
+        set_fixed_str(&mut buffer, "a\0b"),
+        Err(FixedStrError::InteriorNul { position: 1 })
+    );
+    // rejected values don't change the buffer
+    assert_eq!(gb18030_cstr_to_str_i8(&buffer), "cu2101");
+}
+
+#[test]
+fn test_string_field_setter() {
+    let mut instrument = YDInstrument::default();
+    instrument.set_instrument_id("c2103-C-2620").unwrap();
+    assert_eq!(gb18030_cstr_to_str_i8(&instrument.InstrumentID), "c2103-C-2620");
+    assert!(instrument.set_instrument_id(&"9".repeat(32)).is_err());
+}
@@ -0,0 +1,116 @@
+use yd_client_sys::{
+    bindings::{YDCombPositionDef, YDExchange, YDInstrument},
+    data_types::{
+        Direction, HedgeFlag, MarkOrderType, OffsetFlag, OrderFlag, OrderGroupRefControl, OrderType, StockHedgeFlag,
+    },
+    CombPositionOrder, Connection, InputOrderBuilder, InputOrderError, LimitOrder, Mark, MarketOrder, OptionExecute,
+    OrderGroup,
+};
+
+fn exchange(exchange_id: &str, use_today_position: bool) -> YDExchange {
+    let mut exchange = YDExchange {
+        UseTodayPosition: use_today_position,
+        ..Default::default()
+    };
+    exchange.set_exchange_id(exchange_id).unwrap();
+    exchange
+}
+
+// builder 要求 m_pExchange 有效，测试中的 exchange 比 builder 活得更久
+fn instrument(exchange: &YDExchange) -> YDInstrument {
+    YDInstrument {
+        m_pExchange: exchange,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_limit_order() {
+    let shfe = exchange("SHFE", true);
+    let cu2401 = instrument(&shfe);
+    let mut order = unsafe { LimitOrder::new(&cu2401, Direction::Buy, OffsetFlag::CloseToday, 68000.0, 2) };
+    order.options.order_ref = 7;
+    order.options.connection = Connection::Fixed(3);
+    order.options.order_group = Some(OrderGroup {
+        id: 1,
+        ref_control: OrderGroupRefControl::IncreaseOne,
+    });
+    let input_order = order.build().unwrap();
+    assert_eq!(input_order.direction(), Ok(Direction::Buy));
+    assert_eq!(input_order.offset_flag(), Ok(OffsetFlag::CloseToday));
+    assert_eq!(input_order.order_type(), Ok(OrderType::Limit));
+    assert_eq!(unsafe { input_order.__bindgen_anon_1.Price }, 68000.0);
+    assert_eq!((input_order.OrderVolume, input_order.OrderRef), (2, 7));
+    assert_eq!((input_order.ConnectionID, input_order.OrderGroupID), (3, 1));
+    assert_eq!(input_order.group_order_ref_control(), Ok(OrderGroupRefControl::IncreaseOne));
+
+    // SHFE only accepts CloseToday and CloseYesterday for close
+    order.offset_flag = OffsetFlag::Close;
+    assert!(matches!(order.build(), Err(InputOrderError::InvalidOffsetFlag { .. })));
+    order.offset_flag = OffsetFlag::Open;
+    order.price = f64::NAN;
+    assert!(matches!(order.build(), Err(InputOrderError::InvalidPrice { .. })));
+}
+
+#[test]
+fn test_covered_order() {
+    let sse = exchange("SSE", false);
+    let option = instrument(&sse);
+    let mut order = unsafe { LimitOrder::new(&option, Direction::Sell, OffsetFlag::Open, 0.05, 1) };
+    assert_eq!(order.build().unwrap().futures_hedge_flag(), Ok(HedgeFlag::Speculation));
+    order.hedge_flag = StockHedgeFlag::Covered.into();
+    let covered = order.build().unwrap();
+    assert_eq!(covered.hedge_flag(), i32::from(StockHedgeFlag::Covered));
+    assert_eq!(covered.stock_hedge_flag(), Ok(StockHedgeFlag::Covered));
+}
+
+#[test]
+fn test_close_today_outside_shfe() {
+    let dce = exchange("DCE", false);
+    let m2405 = instrument(&dce);
+    let order = unsafe { MarketOrder::new(&m2405, Direction::Sell, OffsetFlag::CloseToday, 1) };
+    assert_eq!(
+        order.build().err(),
+        Some(InputOrderError::InvalidOffsetFlag {
+            offset_flag: OffsetFlag::CloseToday,
+            exchange_id: "DCE".to_string()
+        })
+    );
+    assert!(unsafe { MarketOrder::new(&m2405, Direction::Sell, OffsetFlag::Close, 1) }.build().is_ok());
+    assert!(unsafe { MarketOrder::new(&m2405, Direction::Sell, OffsetFlag::Close, 0) }.build().is_err());
+    let without_exchange = YDInstrument::default();
+    assert_eq!(
+        unsafe { MarketOrder::new(&without_exchange, Direction::Sell, OffsetFlag::Close, 1) }.build().err(),
+        Some(InputOrderError::MissingExchange)
+    );
+}
+
+#[test]
+fn test_flagged_orders() {
+    let dce = exchange("DCE", false);
+    let m2405 = instrument(&dce);
+    let execute = unsafe { OptionExecute::execute(&m2405, OffsetFlag::Close, 1) }.build().unwrap();
+    assert_eq!(execute.yd_order_flag(), Ok(OrderFlag::OptionExecute));
+    // DCE doesn't support abandoning execution
+    assert!(matches!(
+        unsafe { OptionExecute::abandon(&m2405, OffsetFlag::Close, 1) }.build(),
+        Err(InputOrderError::OrderFlagNotSupported { .. })
+    ));
+    let mark = unsafe { Mark::cancel(&m2405, MarkOrderType::PositionOffsetMark) }.build().unwrap();
+    assert_eq!((mark.direction(), mark.yd_order_flag()), (Ok(Direction::Sell), Ok(OrderFlag::Mark)));
+
+    let comb_position_def = YDCombPositionDef {
+        m_pExchange: &dce,
+        ..Default::default()
+    };
+    assert!(unsafe { CombPositionOrder::make(&comb_position_def, 1) }.build().is_ok());
+    // CombPositionDetailID is only for SSE and SZSE
+    assert!(unsafe { CombPositionOrder::split_detail(&comb_position_def, 5, 1) }.build().is_err());
+    let sse = exchange("SSE", false);
+    let comb_position_def = YDCombPositionDef {
+        m_pExchange: &sse,
+        ..Default::default()
+    };
+    let split = unsafe { CombPositionOrder::split_detail(&comb_position_def, 5, 1) }.build().unwrap();
+    assert_eq!(unsafe { split.__bindgen_anon_1.__bindgen_anon_1.CombPositionDetailID }, 5);
+}
@@ -0,0 +1,68 @@
+use yd_client_sys::{
+    bindings::{YDCancelOrder, YDCancelQuote, YDExchange, YDInstrument},
+    data_types::{ConnectionSelectionType, OffsetFlag, QuoteFlag},
+    CancelTarget, Connection, InputOrderError, InputQuote, QuoteSide,
+};
+
+#[test]
+fn test_input_quote() {
+    let mut shfe = YDExchange {
+        UseTodayPosition: true,
+        ..Default::default()
+    };
+    shfe.set_exchange_id("SHFE").unwrap();
+    let cu2401c68000 = YDInstrument {
+        m_pExchange: &shfe,
+        ..Default::default()
+    };
+    let mut quote = unsafe {
+        InputQuote::new(
+            &cu2401c68000,
+            QuoteSide::new(100.0, 1, OffsetFlag::Open),
+            QuoteSide::new(102.0, 2, OffsetFlag::CloseToday),
+        )
+    };
+    quote.quote_flag = QuoteFlag::ResponseOfRFQ | QuoteFlag::ReplaceLastQuote;
+    quote.options.order_ref = 9;
+    let input_quote = quote.build().unwrap();
+    assert_eq!((input_quote.BidPrice, input_quote.AskPrice), (100.0, 102.0));
+    assert_eq!((input_quote.BidVolume, input_quote.AskVolume, input_quote.OrderRef), (1, 2, 9));
+    assert_eq!(input_quote.ask_offset_flag(), Ok(OffsetFlag::CloseToday));
+    assert_eq!(input_quote.yd_quote_flag(), QuoteFlag::all());
+
+    quote.ask.offset_flag = OffsetFlag::Close;
+    assert!(matches!(quote.build(), Err(InputOrderError::InvalidOffsetFlag { .. })));
+    quote.ask.offset_flag = OffsetFlag::CloseToday;
+    quote.bid.volume = 0;
+    assert_eq!(quote.build().err(), Some(InputOrderError::InvalidVolume { volume: 0 }));
+}
+
+#[test]
+fn test_cancel_target() {
+    let by_sys_id = YDCancelOrder::new(CancelTarget::SysId(42), Connection::Fixed(2)).unwrap();
+    assert_eq!(unsafe { by_sys_id.__bindgen_anon_1.OrderSysID }, 42);
+    assert_eq!((by_sys_id.OrderGroupID, by_sys_id.LongOrderSysID), (0, 0));
+    assert_eq!(by_sys_id.connection_selection_type(), Ok(ConnectionSelectionType::Fixed));
+    assert_eq!(by_sys_id.ConnectionID, 2);
+
+    let by_long_sys_id = YDCancelOrder::new(CancelTarget::LongSysId(1 << 40), Connection::Any).unwrap();
+    assert_eq!(by_long_sys_id.LongOrderSysID, 1 << 40);
+
+    // OrderRef shares the union with OrderSysID, and is only used when OrderGroupID != 0
+    let target = CancelTarget::GroupRef {
+        group_id: 3,
+        order_ref: 7,
+    };
+    let by_group_ref = YDCancelQuote::new(target, Connection::Any).unwrap();
+    assert_eq!((by_group_ref.OrderGroupID, unsafe { by_group_ref.__bindgen_anon_1.OrderRef }), (3, 7));
+
+    let target = CancelTarget::GroupRef {
+        group_id: 0,
+        order_ref: 7,
+    };
+    assert_eq!(
+        YDCancelOrder::new(target, Connection::Any).err(),
+        Some(InputOrderError::InvalidOrderGroupId { order_group_id: 0 })
+    );
+    assert!(YDCancelQuote::new(CancelTarget::SysId(1), Connection::Prefered(64)).is_err());
+}
@@ -0,0 +1,106 @@
+use yd_client_sys::{
+    bindings::{YDAccountInstrumentInfo, YDExchange, YDInstrument, YDMarketData},
+    data_types::{Direction, OffsetFlag, TradeRight},
+    yd_error::YdError,
+    InputOrderBuilder, LimitOrder, MarketOrder, PreTradeCheck, PreTradeError, TradingUsage,
+};
+
+// builder 和 PreTradeCheck 要求 m_pExchange 和 m_pMarketData 有效，测试中的 exchange 和 market_data 活得更久
+fn instrument(exchange: &YDExchange, market_data: &YDMarketData) -> YDInstrument {
+    YDInstrument {
+        m_pExchange: exchange,
+        m_pMarketData: market_data,
+        Tick: 0.2,
+        MinLimitOrderVolume: 1,
+        MaxLimitOrderVolume: 100,
+        MinMarketOrderVolume: 1,
+        MaxMarketOrderVolume: 10,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_price_and_volume() {
+    let exchange = YDExchange::default();
+    let market_data = YDMarketData {
+        LowerLimitPrice: 3000.0,
+        UpperLimitPrice: 3400.0,
+        ..Default::default()
+    };
+    let ic2401 = instrument(&exchange, &market_data);
+    let check = unsafe { PreTradeCheck::new(&ic2401) };
+    let order = |price, volume| {
+        unsafe { LimitOrder::new(&ic2401, Direction::Buy, OffsetFlag::Open, price, volume) }.build().unwrap()
+    };
+
+    assert_eq!(check.check(&order(3200.4, 1)), Ok(()));
+    assert_eq!(check.check(&order(3400.0, 100)), Ok(()));
+    let error = check.check(&order(3200.3, 1)).unwrap_err();
+    assert_eq!(error.yd_error(), YdError::PriceTickError);
+    assert!(matches!(
+        check.check(&order(3400.2, 1)),
+        Err(PreTradeError::PriceOutOfLimit { .. })
+    ));
+    assert_eq!(
+        check.check(&order(3200.0, 101)),
+        Err(PreTradeError::InvalidOrderVolume {
+            volume: 101,
+            min_volume: 1,
+            max_volume: 100
+        })
+    );
+    // market orders use the market order volume limits and ignore the price
+    let market_order = unsafe { MarketOrder::new(&ic2401, Direction::Sell, OffsetFlag::Open, 11) }.build().unwrap();
+    assert_eq!(check.check(&market_order).unwrap_err().yd_error(), YdError::InvalidOrderVolume);
+}
+
+#[test]
+fn test_trading_right_and_constraints() {
+    let exchange = YDExchange::default();
+    let ic2401 = YDInstrument {
+        // price limits are not checked without market data
+        m_pMarketData: std::ptr::null(),
+        ..instrument(&exchange, &YDMarketData::default())
+    };
+    let mut close_only = YDAccountInstrumentInfo {
+        TradingRight: TradeRight::CloseOnly as i32,
+        ..Default::default()
+    };
+    for constraint in close_only.TradingConstraints.iter_mut() {
+        (constraint.OpenLimit, constraint.PositionLimit) = (-1, -1);
+        (constraint.DirectionOpenLimit, constraint.DirectionPositionLimit) = ([-1, 10], [-1, -1]);
+    }
+    let allow = YDAccountInstrumentInfo {
+        TradingRight: TradeRight::Allow as i32,
+        ..close_only
+    };
+    let mut check = unsafe { PreTradeCheck::new(&ic2401) };
+    check.account_instrument_info = Some(&close_only);
+    check.usage = Some(TradingUsage {
+        direction_open_volume: [0, 8],
+        ..Default::default()
+    });
+    let open = |direction, volume| {
+        unsafe { LimitOrder::new(&ic2401, direction, OffsetFlag::Open, 3200.0, volume) }.build().unwrap()
+    };
+    let close = unsafe { LimitOrder::new(&ic2401, Direction::Sell, OffsetFlag::Close, 3200.0, 1) }.build().unwrap();
+
+    assert_eq!(
+        check.check(&open(Direction::Buy, 1)),
+        Err(PreTradeError::NoTradingRight {
+            trading_right: TradeRight::CloseOnly
+        })
+    );
+    assert_eq!(check.check(&close), Ok(()));
+
+    check.account_instrument_info = Some(&allow);
+    assert_eq!(check.check(&open(Direction::Buy, 50)), Ok(()));
+    assert_eq!(check.check(&open(Direction::Sell, 2)), Ok(()));
+    assert_eq!(
+        check.check(&open(Direction::Sell, 3)),
+        Err(PreTradeError::OrderOpenLimitExceed {
+            open_volume: 11,
+            open_limit: 10
+        })
+    );
+}
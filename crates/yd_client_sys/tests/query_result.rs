@@ -0,0 +1,21 @@
+#![cfg(not(feature = "dlopen"))]
+use yd_client_sys::create_yd_api;
+
+#[test]
+fn test_get_configs() {
+    let mut handle = create_yd_api("examples/config.txt");
+    let mut api = handle.api_mut().unwrap();
+    {
+        // QueryResult borrows the api, drop it before calling the api again
+        let configs = api
+            .get_configs("TradingServerPort")
+            .unwrap()
+            .expect("getConfigs should not return null");
+        assert_eq!(configs.iter().count(), configs.len());
+        assert_eq!(configs.iter().len(), configs.len());
+        assert!(!configs.is_empty());
+        assert!(configs.to_strings().iter().all(|port| port == "51000"));
+    }
+    let missing = api.get_configs("NotExistConfig").unwrap();
+    assert!(missing.iter().all(|missing| missing.is_empty()));
+}
@@ -0,0 +1,39 @@
+use yd_client_sys::spi_wrapper::{
+    YDExtendedListenerOutput, YDExtendedListenerRingSpi, YDExtendedListenerSpi, YDExtendedListenerStream,
+    YDListenerNotifyOrderPacket, YDListenerOutput, YDListenerRingSpi, YDListenerSpi, YDListenerStream,
+};
+
+fn assert_send_static<T: Send + 'static>() {}
+
+#[test]
+fn test_packets_are_owned() {
+    assert_send_static::<YDListenerOutput>();
+    assert_send_static::<YDExtendedListenerOutput>();
+    assert_send_static::<YDListenerStream>();
+    assert_send_static::<YDExtendedListenerStream>();
+}
+
+#[test]
+fn test_generated_listeners_can_be_started() {
+    // YDApiHandle::start only accepts Send listeners, they are called on the libyd thread
+    assert_send_static::<YDListenerSpi>();
+    assert_send_static::<YDListenerRingSpi>();
+    assert_send_static::<YDExtendedListenerSpi>();
+    assert_send_static::<YDExtendedListenerRingSpi>();
+}
+
+#[test]
+fn test_catalog_objects_are_kept_as_refs() {
+    let packet = YDListenerNotifyOrderPacket {
+        order: Default::default(),
+        instrument_ref: 1,
+        account_ref: 2,
+    };
+    let output = YDListenerOutput::NotifyOrder(packet);
+    std::thread::spawn(move || match output {
+        YDListenerOutput::NotifyOrder(packet) => assert_eq!((packet.instrument_ref, packet.account_ref), (1, 2)),
+        _ => unreachable!(),
+    })
+    .join()
+    .unwrap();
+}
@@ -0,0 +1,67 @@
+use futures::{FutureExt, StreamExt};
+use yd_client_sys::{
+    bindings::{YDMarketData, YDOrder},
+    spi_wrapper::{create_spi_with_queue, YDListenerOutput, YDListenerStream, YDListenerTrait},
+    OverflowPolicy, SpiQueueConfig,
+};
+
+fn market_data(instrument_ref: i32, last_price: f64) -> YDMarketData {
+    YDMarketData {
+        InstrumentRef: instrument_ref,
+        LastPrice: last_price,
+        ..Default::default()
+    }
+}
+
+fn drain(stream: &mut YDListenerStream) -> Vec<YDListenerOutput> {
+    std::iter::from_fn(|| stream.next().now_or_never().flatten()).collect()
+}
+
+#[test]
+fn test_drop_oldest_market_data_keeps_orders() {
+    let (mut stream, mut spi) = create_spi_with_queue(SpiQueueConfig::bounded(2, OverflowPolicy::DropOldestMarketData));
+    let order = YDOrder::default();
+    spi.notify_market_data(&market_data(1, 1.0));
+    spi.notify_order(&order, &Default::default(), &Default::default());
+    spi.notify_market_data(&market_data(2, 2.0));
+    spi.notify_market_data(&market_data(3, 3.0));
+
+    let metrics = stream.metrics();
+    assert_eq!((metrics.depth, metrics.dropped, metrics.high_water_mark), (2, 2, 2));
+    let events = drain(&mut stream);
+    assert!(matches!(events[0], YDListenerOutput::NotifyOrder(_)));
+    assert!(matches!(&events[1], YDListenerOutput::NotifyMarketData(packet) if packet.market_data.InstrumentRef == 3));
+    assert_eq!(stream.metrics().depth, 0);
+}
+
+#[test]
+fn test_coalesce_market_data_per_instrument() {
+    let (mut stream, mut spi) = create_spi_with_queue(SpiQueueConfig::bounded(2, OverflowPolicy::CoalesceMarketData));
+    spi.notify_market_data(&market_data(1, 1.0));
+    spi.notify_market_data(&market_data(2, 2.0));
+    spi.notify_market_data(&market_data(1, 1.5));
+
+    assert_eq!(stream.metrics().dropped, 1);
+    let prices: Vec<_> = drain(&mut stream)
+        .into_iter()
+        .map(|event| match event {
+            YDListenerOutput::NotifyMarketData(packet) => packet.market_data.LastPrice,
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(prices, vec![1.5, 2.0]);
+}
+
+#[test]
+fn test_stream_ends_after_spi_is_released() {
+    let (mut stream, mut spi) = create_spi_with_queue(SpiQueueConfig::default());
+    spi.notify_market_data(&market_data(1, 1.0));
+    assert!(matches!(stream.next().now_or_never(), Some(Some(_))));
+    // pending while the listener is alive
+    assert!(stream.next().now_or_never().is_none());
+    spi.notify_market_data(&market_data(2, 2.0));
+    // libyd releases the listener after notifyAfterApiDestroy, the queued events are still taken
+    drop(spi);
+    assert!(matches!(stream.next().now_or_never(), Some(Some(YDListenerOutput::NotifyMarketData(_)))));
+    assert!(matches!(stream.next().now_or_never(), Some(None)));
+}
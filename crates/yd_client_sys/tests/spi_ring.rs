@@ -0,0 +1,78 @@
+use yd_client_sys::{
+    bindings::{YDMarketData, YDOrder},
+    spi_wrapper::{create_spi_with_ring, YDListenerOutput, YDListenerTrait},
+    RingOverflowPolicy, SpiRingConfig,
+};
+
+fn order(order_ref: i32) -> YDOrder {
+    YDOrder {
+        OrderRef: order_ref,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_ring_drops_market_data_when_full() {
+    let (mut consumer, mut spi) = create_spi_with_ring(SpiRingConfig::new(2, RingOverflowPolicy::DropMarketData));
+    spi.notify_market_data(&YDMarketData::default());
+    spi.notify_order(&order(7), &Default::default(), &Default::default());
+    spi.notify_market_data(&YDMarketData::default());
+
+    assert_eq!((consumer.len(), consumer.dropped()), (2, 1));
+    assert!(matches!(consumer.pop(), Some(YDListenerOutput::NotifyMarketData(_))));
+    assert!(matches!(consumer.pop(), Some(YDListenerOutput::NotifyOrder(packet)) if packet.order.OrderRef == 7));
+    assert!(consumer.pop().is_none());
+}
+
+#[test]
+fn test_ring_spills_trading_events_in_order() {
+    let (mut consumer, mut spi) = create_spi_with_ring(SpiRingConfig::new(1, RingOverflowPolicy::DropMarketData));
+    for order_ref in 0..3 {
+        spi.notify_order(&order(order_ref), &Default::default(), &Default::default());
+    }
+    // market data is dropped while trading events are spilled, even if the ring has room
+    assert!(matches!(consumer.pop(), Some(YDListenerOutput::NotifyOrder(packet)) if packet.order.OrderRef == 0));
+    spi.notify_market_data(&YDMarketData::default());
+    assert_eq!((consumer.len(), consumer.spilled(), consumer.dropped()), (2, 2, 1));
+
+    let order_refs: Vec<_> = std::iter::from_fn(|| consumer.pop())
+        .map(|event| match event {
+            YDListenerOutput::NotifyOrder(packet) => packet.order.OrderRef,
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(order_refs, vec![1, 2]);
+    spi.notify_market_data(&YDMarketData::default());
+    assert!(matches!(consumer.pop(), Some(YDListenerOutput::NotifyMarketData(_))));
+}
+
+#[test]
+fn test_ring_consumer_on_another_thread() {
+    let (mut consumer, mut spi) = create_spi_with_ring(SpiRingConfig::new(4, RingOverflowPolicy::SpinOnMarketData));
+    let handle = std::thread::spawn(move || {
+        let mut order_refs = vec![];
+        while order_refs.len() < 100 {
+            match consumer.pop() {
+                Some(YDListenerOutput::NotifyOrder(packet)) => order_refs.push(packet.order.OrderRef),
+                _ => std::thread::yield_now(),
+            }
+        }
+        order_refs
+    });
+    for order_ref in 0..100 {
+        spi.notify_order(&order(order_ref), &Default::default(), &Default::default());
+    }
+    assert_eq!(handle.join().unwrap(), (0..100).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_ring_is_closed_after_spi_is_released() {
+    let (mut consumer, mut spi) = create_spi_with_ring(SpiRingConfig::new(1, RingOverflowPolicy::DropMarketData));
+    spi.notify_order(&order(1), &Default::default(), &Default::default());
+    spi.notify_order(&order(2), &Default::default(), &Default::default());
+    drop(spi);
+    // events in the ring and the spill queue are still taken
+    assert!(!consumer.is_closed());
+    assert_eq!(std::iter::from_fn(|| consumer.pop()).count(), 2);
+    assert!(consumer.is_closed());
+}
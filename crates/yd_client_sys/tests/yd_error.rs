@@ -0,0 +1,23 @@
+use yd_client_sys::{yd_error::lookup_error_message, ErrorExchange, YdError};
+
+#[test]
+fn test_yd_error_from_code() {
+    assert_eq!(YdError::from_code(0), None);
+    let error = YdError::from_code(501).unwrap();
+    assert_eq!(error, YdError::TooManyInMultiOrders);
+    assert_eq!(error.code(), 501);
+    assert_eq!(error.exchange(), Some(ErrorExchange::Yd));
+    assert_eq!(error.message_zh(), Some("批量报单报单数超过16个"));
+}
+
+#[test]
+fn test_exchange_error_depends_on_exchange() {
+    let exchange = ErrorExchange::from_exchange_id("INE");
+    assert_eq!(exchange, Some(ErrorExchange::ShfeIne));
+    let error = YdError::from_exchange_code(1001, exchange).unwrap();
+    assert_eq!(error.exchange(), Some(ErrorExchange::ShfeIne));
+    assert!(error.message_zh().is_some());
+    assert_eq!(YdError::from_code(1001).unwrap().message_zh(), None);
+    assert_eq!(lookup_error_message(1001, ErrorExchange::ShfeIne), Some("会话不正确"));
+    assert_eq!(lookup_error_message(1001, ErrorExchange::Czce), Some("操作失败"));
+}
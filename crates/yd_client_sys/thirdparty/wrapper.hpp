@@ -1,5 +1,5 @@
-#include "ydClient/ydAPI_c++/include/ydApi.h"
-#include "ydClient/ydAPI_c++/include/ydDataStruct.h"
-#include "ydClient/ydAPI_c++/include/ydDataType.h"
-#include "ydClient/ydAPI_c++/include/ydError.h"
-#include "ydClient/ydAPI_c++/include/ydUtil.h"
+#include "ydApi.h"
+#include "ydDataStruct.h"
+#include "ydDataType.h"
+#include "ydError.h"
+#include "ydUtil.h"